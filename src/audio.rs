@@ -0,0 +1,261 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use rodio::source::SineWave;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+
+// Two notes each, soft enough to sit under other audio: rising when work ends, falling when a
+// break ends, so the two can be told apart without looking
+const WORK_DONE_NOTES: [(f32, u64); 2] = [(880.0, 180), (1320.0, 320)]; // Hz, milliseconds
+const BREAK_DONE_NOTES: [(f32, u64); 2] = [(1320.0, 180), (880.0, 320)];
+const CHIME_LEVEL: f32 = 0.25;
+const NOISE_LEVEL: f32 = 0.2; // Generated noise is full scale, far louder than most loops
+const NOISE_SAMPLE_RATE: u32 = 44_100;
+
+/// Which period just ended, picking the built-in chime
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Chime {
+    WorkDone,
+    BreakDone,
+}
+
+impl Chime {
+    fn notes(&self) -> [(f32, u64); 2] {
+        match self {
+            Chime::WorkDone => WORK_DONE_NOTES,
+            Chime::BreakDone => BREAK_DONE_NOTES,
+        }
+    }
+}
+
+/// Play the period-complete sound on a detached thread: the custom file if one is set and
+/// decodes, otherwise the built-in chime. The sound ramps up over fade_in so the alert doesn't
+/// start at full volume.
+pub fn play_completion_sound(sound_file: Option<PathBuf>, chime: Chime, volume: f32, fade_in: Duration) {
+    std::thread::spawn(move || {
+        // The stream must outlive playback, so it stays in this thread until the sink is done
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("Failed to open audio output: {}", e);
+                return;
+            }
+        };
+        let sink = match Sink::try_new(&handle) {
+            Ok(sink) => sink,
+            Err(e) => {
+                eprintln!("Failed to play sound: {}", e);
+                return;
+            }
+        };
+
+        sink.set_volume(volume.clamp(0.0, 1.0));
+        match sound_file.as_deref().map(load_sound) {
+            Some(Ok(sound)) => sink.append(sound.fade_in(fade_in)),
+            Some(Err(e)) => {
+                eprintln!("{}; playing the built-in chime instead", e);
+                append_chime(&sink, chime, fade_in);
+            }
+            None => append_chime(&sink, chime, fade_in),
+        }
+        sink.sleep_until_end();
+    });
+}
+
+/// Check that a sound file can be played, for warning about it when it's picked
+pub fn check_sound_file(path: &Path) -> Result<(), String> {
+    load_sound(path).map(|_| ())
+}
+
+fn load_sound(path: &Path) -> Result<Decoder<BufReader<File>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    Decoder::new(BufReader::new(file)).map_err(|e| format!("Failed to decode {}: {}", path.display(), e))
+}
+
+fn append_chime(sink: &Sink, chime: Chime, fade_in: Duration) {
+    for (i, (frequency, millis)) in chime.notes().into_iter().enumerate() {
+        let note = SineWave::new(frequency)
+            .take_duration(Duration::from_millis(millis))
+            .amplify(CHIME_LEVEL);
+        // Only the first note ramps up
+        if i == 0 {
+            sink.append(note.fade_in(fade_in));
+        } else {
+            sink.append(note);
+        }
+    }
+}
+
+/// What the ambient loop plays, see Settings::ambient_sound
+#[derive(Debug, PartialEq, Clone)]
+pub enum AmbientSource {
+    WhiteNoise,
+    BrownNoise,
+    File(PathBuf),
+}
+
+enum AmbientCommand {
+    Play(AmbientSource),
+    Stop,
+    SetVolume(f32),
+}
+
+/// Background loop for work sessions. A single thread owns the audio output for the
+/// player's lifetime, so starting and stopping the loop across many sessions doesn't
+/// spawn anything new. The thread exits when the player is dropped.
+pub struct AmbientPlayer {
+    sender: Sender<AmbientCommand>,
+    playing: Option<AmbientSource>,
+    volume: f32,
+}
+
+impl AmbientPlayer {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || run_ambient(receiver));
+        Self {
+            sender,
+            playing: None,
+            volume: 0.0,
+        }
+    }
+
+    /// Play `source` at `volume`, or stop with None. Cheap to call every frame: commands are
+    /// only sent when something changed.
+    pub fn set(&mut self, source: Option<AmbientSource>, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        if volume != self.volume {
+            self.volume = volume;
+            self.send(AmbientCommand::SetVolume(volume));
+        }
+        if source == self.playing {
+            return;
+        }
+        self.playing = source.clone();
+        self.send(match source {
+            Some(source) => AmbientCommand::Play(source),
+            None => AmbientCommand::Stop,
+        });
+    }
+
+    fn send(&self, command: AmbientCommand) {
+        // Only fails once the audio thread has given up, which it logs
+        let _ = self.sender.send(command);
+    }
+}
+
+fn run_ambient(receiver: Receiver<AmbientCommand>) {
+    // Opened on first use, so users who never turn the loop on don't hold an audio device
+    let mut output: Option<(OutputStream, OutputStreamHandle)> = None;
+    let mut sink: Option<Sink> = None;
+    let mut volume = 1.0;
+
+    for command in receiver {
+        match command {
+            AmbientCommand::Play(source) => {
+                if let Some(old) = sink.take() {
+                    old.stop();
+                }
+                if output.is_none() {
+                    match OutputStream::try_default() {
+                        Ok(opened) => output = Some(opened),
+                        Err(e) => {
+                            eprintln!("Failed to open audio output: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                let Some((_, handle)) = &output else { continue };
+                let new_sink = match Sink::try_new(handle) {
+                    Ok(sink) => sink,
+                    Err(e) => {
+                        eprintln!("Failed to play ambient sound: {}", e);
+                        continue;
+                    }
+                };
+                new_sink.set_volume(volume);
+                match source {
+                    AmbientSource::WhiteNoise => new_sink.append(Noise::white().amplify(NOISE_LEVEL)),
+                    AmbientSource::BrownNoise => new_sink.append(Noise::brown().amplify(NOISE_LEVEL)),
+                    AmbientSource::File(path) => match load_sound(&path) {
+                        Ok(sound) => new_sink.append(sound.repeat_infinite()),
+                        Err(e) => {
+                            eprintln!("{}; playing brown noise instead", e);
+                            new_sink.append(Noise::brown().amplify(NOISE_LEVEL));
+                        }
+                    },
+                }
+                sink = Some(new_sink);
+            }
+            AmbientCommand::Stop => {
+                if let Some(old) = sink.take() {
+                    old.stop();
+                }
+            }
+            AmbientCommand::SetVolume(new_volume) => {
+                volume = new_volume;
+                if let Some(sink) = &sink {
+                    sink.set_volume(volume);
+                }
+            }
+        }
+    }
+}
+
+/// Endless generated noise, so the bundled loops need no audio files
+struct Noise {
+    seed: u32,
+    brown: Option<f32>, // Running level for brown noise; None = white
+}
+
+impl Noise {
+    fn white() -> Self {
+        Self { seed: 0x9E37_79B9, brown: None }
+    }
+
+    fn brown() -> Self {
+        Self { seed: 0x9E37_79B9, brown: Some(0.0) }
+    }
+}
+
+impl Iterator for Noise {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        // xorshift32, plenty random for noise
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        let white = self.seed as f32 / u32::MAX as f32 * 2.0 - 1.0;
+
+        Some(match &mut self.brown {
+            None => white,
+            // Leaky integration of white noise: a deep, rain-like rumble
+            Some(level) => {
+                *level = (*level + 0.02 * white) / 1.02;
+                *level * 3.5
+            }
+        })
+    }
+}
+
+impl Source for Noise {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        NOISE_SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
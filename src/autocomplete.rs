@@ -0,0 +1,114 @@
+use eframe::egui::{self, Key, Modifiers};
+
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Single-line text edit that offers earlier entries starting with what has been typed.
+/// Up/Down move through the suggestions, Tab or Enter accepts, clicking one picks it.
+pub fn autocomplete_edit(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    text: &mut String,
+    candidates: &[String],
+    hint: &str,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_salt);
+    let popup_id = id.with("suggestions");
+    let selected_id = id.with("selected");
+
+    // Suggestions shown last frame, which the keys below act on
+    let offered = matching(text, candidates);
+    let focused = ui.memory(|m| m.has_focus(id));
+    let popup_open = focused && !offered.is_empty() && ui.memory(|m| m.is_popup_open(popup_id));
+    let mut selected = ui
+        .data(|d| d.get_temp::<usize>(selected_id))
+        .unwrap_or(0)
+        .min(offered.len().saturating_sub(1));
+    let mut accepted = None;
+
+    // Take the navigation keys before the text edit sees them
+    if popup_open {
+        ui.input_mut(|i| {
+            if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                selected = (selected + 1) % offered.len();
+            }
+            if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                selected = (selected + offered.len() - 1) % offered.len();
+            }
+            if i.consume_key(Modifiers::NONE, Key::Tab) || i.consume_key(Modifiers::NONE, Key::Enter) {
+                accepted = Some(selected);
+            }
+        });
+    }
+
+    let mut response = ui.add(
+        egui::TextEdit::singleline(text)
+            .id(id)
+            .hint_text(hint)
+            .desired_width(ui.available_width().min(240.0))
+            // Keeps Tab for accepting a suggestion instead of moving focus
+            .lock_focus(popup_open),
+    );
+
+    if response.changed() {
+        selected = 0;
+    }
+
+    let matches = matching(text, candidates);
+    if response.has_focus() && !matches.is_empty() && accepted.is_none() {
+        ui.memory_mut(|m| m.open_popup(popup_id));
+    } else if matches.is_empty() {
+        ui.memory_mut(|m| {
+            if m.is_popup_open(popup_id) {
+                m.close_popup();
+            }
+        });
+    }
+
+    let picked = egui::popup_below_widget(
+        ui,
+        popup_id,
+        &response,
+        egui::PopupCloseBehavior::CloseOnClickOutside,
+        |ui| {
+            let mut picked = None;
+            for (i, suggestion) in matches.iter().enumerate() {
+                if ui.selectable_label(i == selected, suggestion.as_str()).clicked() {
+                    picked = Some(i);
+                }
+            }
+            picked
+        },
+    )
+    .flatten();
+
+    let choice = accepted
+        .and_then(|i| offered.get(i))
+        .or_else(|| picked.and_then(|i| matches.get(i)))
+        .cloned();
+    if let Some(choice) = choice {
+        *text = choice;
+        response.mark_changed();
+        ui.memory_mut(|m| m.close_popup());
+        selected = 0;
+    }
+
+    ui.data_mut(|d| d.insert_temp(selected_id, selected));
+    response
+}
+
+/// Candidates starting with text, ignoring case, leaving out an exact match
+fn matching(text: &str, candidates: &[String]) -> Vec<String> {
+    let typed = text.trim().to_lowercase();
+    if typed.is_empty() {
+        return Vec::new();
+    }
+    candidates
+        .iter()
+        .filter(|c| {
+            let candidate = c.to_lowercase();
+            candidate.starts_with(&typed) && candidate != typed
+        })
+        .take(MAX_SUGGESTIONS)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,91 @@
+use std::time::Instant;
+
+use chrono::{DateTime, Local, Utc};
+
+/// Source of the current time for the timer and the date queries. Everything that asks
+/// "what time is it" goes through one of these, so a fixed or stepped clock can stand in
+/// for the system one and timing behaviour can be replayed exactly.
+pub trait Clock: Send + Sync {
+    /// Monotonic time, for countdown deadlines and pauses
+    fn now_instant(&self) -> Instant;
+
+    /// Wall-clock time, for timestamps and day boundaries
+    fn now_utc(&self) -> DateTime<Utc>;
+
+    fn now_local(&self) -> DateTime<Local> {
+        self.now_utc().with_timezone(&Local)
+    }
+}
+
+/// The real clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock for tests that only moves when told to, starting at a given wall-clock time.
+/// Both readings advance together, as they would for a real clock nobody adjusts.
+#[cfg(test)]
+pub struct MockClock {
+    start_instant: Instant,
+    start_utc: DateTime<Utc>,
+    elapsed: std::sync::Mutex<std::time::Duration>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub fn at(start_utc: DateTime<Utc>) -> Self {
+        Self {
+            start_instant: Instant::now(),
+            start_utc,
+            elapsed: std::sync::Mutex::new(std::time::Duration::ZERO),
+        }
+    }
+
+    pub fn advance(&self, by: std::time::Duration) {
+        *self.elapsed.lock().unwrap() += by;
+    }
+
+    fn elapsed(&self) -> std::time::Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now_instant(&self) -> Instant {
+        self.start_instant + self.elapsed()
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.start_utc + chrono::Duration::from_std(self.elapsed()).expect("Test clock moved too far")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn mock_clock_moves_both_readings_together() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::at(start);
+        let instant = clock.now_instant();
+
+        clock.advance(Duration::from_secs(90));
+
+        assert_eq!(clock.now_utc(), start + chrono::Duration::seconds(90));
+        assert_eq!(clock.now_instant().duration_since(instant), Duration::from_secs(90));
+    }
+}
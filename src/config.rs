@@ -0,0 +1,500 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AmbientSource;
+use crate::db::Database;
+use crate::hotkeys;
+use crate::locale::NumberLocale;
+use crate::theme::MIDNIGHT;
+
+/// Which timestamp decides the day a session belongs to.
+///
+/// A session started at 23:55 and completed at 00:20 counts toward the day it
+/// started under `StartTime` (the default), or toward the next day under
+/// `CompletionTime`. Every day-bucketing query in `Database` follows this rule.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayBoundary {
+    #[default]
+    StartTime,
+    CompletionTime,
+}
+
+impl DayBoundary {
+    /// Column of `work_sessions` used for day bucketing
+    pub fn column(&self) -> &'static str {
+        match self {
+            DayBoundary::StartTime => "started_at",
+            DayBoundary::CompletionTime => "completed_at",
+        }
+    }
+}
+
+/// Light or dark version of the chosen theme preset
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 2] = [ThemeMode::Dark, ThemeMode::Light];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThemeMode::Dark => "Dark",
+            ThemeMode::Light => "Light",
+        }
+    }
+}
+
+/// What Escape does during a work session. Breaks always use it to minimize.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscapeAction {
+    #[default]
+    Nothing,
+    Pause,
+    Minimize,
+}
+
+impl EscapeAction {
+    pub const ALL: [EscapeAction; 3] = [EscapeAction::Nothing, EscapeAction::Pause, EscapeAction::Minimize];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EscapeAction::Nothing => "Nothing",
+            EscapeAction::Pause => "Pause timer",
+            EscapeAction::Minimize => "Minimize window",
+        }
+    }
+}
+
+/// Where settings are kept: config.toml next to the database (the default), or a
+/// `settings` table inside it so a single file holds everything for backup or sync
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum SettingsBackend {
+    #[default]
+    File,
+    Database,
+}
+
+impl SettingsBackend {
+    /// Chosen with the `--settings-in-db` command-line flag
+    pub fn from_args() -> Self {
+        if std::env::args().any(|arg| arg == "--settings-in-db") {
+            SettingsBackend::Database
+        } else {
+            SettingsBackend::File
+        }
+    }
+}
+
+pub const DEFAULT_WORK_MINUTES: u64 = 25;
+pub const DEFAULT_BREAK_MINUTES: u64 = 5;
+pub const DEFAULT_LONG_BREAK_MINUTES: u64 = 15;
+pub const DURATION_MINUTES: RangeInclusive<u64> = 1..=120; // Allowed work and break lengths
+pub const MIN_WINDOW_FLOOR: f32 = 200.0; // Smallest minimum window width or height, in points
+
+fn minutes_or_default(minutes: u64, default: u64) -> u64 {
+    if DURATION_MINUTES.contains(&minutes) {
+        minutes
+    } else {
+        default
+    }
+}
+
+/// What a fullscreen break does when the monitor setup changes under it (dock/undock)
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayChangeAction {
+    #[default]
+    Refullscreen, // Go fullscreen again on whichever display the window landed on
+    Windowed,     // Continue the break in a normal window
+}
+
+impl DisplayChangeAction {
+    pub const ALL: [DisplayChangeAction; 2] = [DisplayChangeAction::Refullscreen, DisplayChangeAction::Windowed];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplayChangeAction::Refullscreen => "Stay fullscreen",
+            DisplayChangeAction::Windowed => "Switch to a window",
+        }
+    }
+}
+
+/// How the window gets noticed once a break runs out
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakEndAction {
+    #[default]
+    Background, // Just leave fullscreen, wherever that puts the window
+    Focus,      // Raise and focus the window
+    Attention,  // Flash/bounce the window without stealing focus
+}
+
+impl BreakEndAction {
+    pub const ALL: [BreakEndAction; 3] = [BreakEndAction::Background, BreakEndAction::Focus, BreakEndAction::Attention];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BreakEndAction::Background => "Stay in the background",
+            BreakEndAction::Focus => "Bring to front",
+            BreakEndAction::Attention => "Request attention",
+        }
+    }
+}
+
+/// Background loop played while a work session runs
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmbientSound {
+    #[default]
+    Off,
+    WhiteNoise,
+    BrownNoise,
+    Custom, // Settings::ambient_file
+}
+
+impl AmbientSound {
+    pub const ALL: [AmbientSound; 4] = [
+        AmbientSound::Off,
+        AmbientSound::WhiteNoise,
+        AmbientSound::BrownNoise,
+        AmbientSound::Custom,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AmbientSound::Off => "Off",
+            AmbientSound::WhiteNoise => "White noise",
+            AmbientSound::BrownNoise => "Brown noise (rain)",
+            AmbientSound::Custom => "Custom file",
+        }
+    }
+}
+
+/// Look and sound tied to a task label, in effect while that label is the current task
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub label: String,      // Task label this profile applies to, matched ignoring case
+    pub theme: String,      // Theme preset; empty = the global theme
+    pub sound_file: String, // Completion sound; empty = the global sound
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub work_minutes: u64,       // Length of a work session, within DURATION_MINUTES
+    pub break_minutes: u64,      // Length of a short break, within DURATION_MINUTES
+    pub long_break_minutes: u64, // Length of the break after a full cycle, within DURATION_MINUTES
+    pub day_boundary: DayBoundary,
+    pub day_start_hour: u32,     // Local hour a new day begins, for night owls (0 = midnight)
+    pub theme: String,           // Name of a theme preset
+    pub theme_mode: ThemeMode,
+    pub strict_mode: bool,       // Breaks can't be skipped
+    pub ask_skip_reason: bool,   // Ask why before skipping a break
+    pub continuous_mode: bool,   // Work sessions run back to back with no breaks
+    pub auto_break: bool,        // Start the break when work ends; otherwise wait for Take a Break
+    pub auto_start_work: bool,   // Start the next work session when a break ends; otherwise wait for Start
+    pub min_session_seconds: u64, // Work ended early with Take Break is saved only if it ran this long
+    pub on_demand_long_break_resets_cycle: bool, // A long break taken early also starts a new cycle
+    pub hide_during_work: bool,  // Hide the window entirely during work instead of minimizing
+    pub idle_threshold_secs: u64, // Pause work after this long without input anywhere, 0 = never
+    pub gentle_first_break: bool, // First break of each cycle shows windowed, not fullscreen
+    pub break_grace_secs: u64,   // Countdown in the window before a break goes fullscreen, 0 = none
+    pub daily_goal_minutes: u32, // Focus time target per day
+    pub daily_goal_sessions: u32, // Pomodoros to aim for each day, 0 = no goal
+    pub goal_notification: bool, // Notify when the session goal is reached
+    pub goal_requires_break: bool, // Sessions count toward the goal only once their break is taken
+    pub welcome_back_enabled: bool, // Greet the user after a few days without a session
+    pub welcome_back_days: u32,
+    pub welcome_back_message: String,
+    pub focus_contract_enabled: bool, // Ask each day for a number of sessions to commit to
+    pub contract_recap_hour: u32, // Local hour the committed vs completed recap is shown
+    pub window_opacity: f32,     // Background opacity of the window, 1.0 = opaque
+    pub min_window_width: f32,   // The window can't be resized below these, see min_window_size
+    pub min_window_height: f32,
+    pub notifications_enabled: bool, // Desktop notification when work or a break ends
+    pub wind_down_enabled: bool, // Warn shortly before a work session ends
+    pub wind_down_lead_secs: u64,
+    pub escape_action: EscapeAction, // Escape during a work session
+    pub hotkey_start_pause: String,  // System-wide shortcut, e.g. "CmdOrCtrl+Shift+P"; empty = none
+    pub hotkey_skip_break: String,
+    pub display_change_action: DisplayChangeAction,
+    pub break_end_action: BreakEndAction,
+    pub reduce_motion: bool,         // Skip screen transitions
+    pub final_minute_seconds: bool,  // Show just the seconds, large, in the last minute
+    pub number_locale: NumberLocale, // Digit grouping and duration wording
+    pub sound_enabled: bool,         // Chime when a work session or break ends
+    pub sound_volume: f32,           // 0.0 to 1.0
+    pub sound_fade_in_ms: u64,       // Ramp at the start of the chime, 0 = none
+    pub sound_file: String,          // Custom completion sound; empty = built-in chime
+    pub chime_repeats: u32,          // Extra chimes until the user interacts, 0 = chime once
+    pub chime_repeat_secs: u64,      // Gap between repeated chimes
+    pub ambient_sound: AmbientSound, // Loop played during work sessions
+    pub ambient_volume: f32,         // 0.0 to 1.0, separate from the chime
+    pub ambient_file: String,        // Loop for AmbientSound::Custom
+    pub webhook_url: String,         // Completed sessions are POSTed here; empty = off
+    pub state_socket_enabled: bool,  // Stream timer state over a Unix socket in the data folder
+    pub stats_completed_only: bool,  // Stats leave out sessions that didn't end naturally
+    pub retention_days: u32,         // Sessions older than this are deleted; 0 = keep forever
+    pub weekday_goals: BTreeMap<String, u32>, // Overrides of the daily goal, keyed "mon".."sun"
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            work_minutes: DEFAULT_WORK_MINUTES,
+            break_minutes: DEFAULT_BREAK_MINUTES,
+            long_break_minutes: DEFAULT_LONG_BREAK_MINUTES,
+            day_boundary: DayBoundary::default(),
+            day_start_hour: 0,
+            theme: MIDNIGHT.name.to_string(),
+            theme_mode: ThemeMode::Dark,
+            strict_mode: false,
+            ask_skip_reason: false,
+            continuous_mode: false,
+            auto_break: true,
+            auto_start_work: false,
+            min_session_seconds: 60,
+            on_demand_long_break_resets_cycle: false,
+            hide_during_work: false,
+            idle_threshold_secs: 120,
+            gentle_first_break: false,
+            break_grace_secs: 0,
+            daily_goal_minutes: 100,
+            daily_goal_sessions: 0,
+            goal_notification: true,
+            goal_requires_break: false,
+            welcome_back_enabled: false,
+            welcome_back_days: 3,
+            welcome_back_message: "Welcome back! Ready for one pomodoro?".to_string(),
+            focus_contract_enabled: false,
+            contract_recap_hour: 20,
+            window_opacity: 1.0,
+            min_window_width: MIN_WINDOW_FLOOR,
+            min_window_height: MIN_WINDOW_FLOOR,
+            notifications_enabled: true,
+            wind_down_enabled: false,
+            wind_down_lead_secs: 60,
+            escape_action: EscapeAction::default(),
+            hotkey_start_pause: hotkeys::DEFAULT_START_PAUSE.to_string(),
+            hotkey_skip_break: hotkeys::DEFAULT_SKIP_BREAK.to_string(),
+            display_change_action: DisplayChangeAction::default(),
+            break_end_action: BreakEndAction::default(),
+            reduce_motion: false,
+            final_minute_seconds: false,
+            number_locale: NumberLocale::default(),
+            sound_enabled: true,
+            sound_volume: 0.7,
+            sound_fade_in_ms: 200,
+            sound_file: String::new(),
+            chime_repeats: 0,
+            chime_repeat_secs: 10,
+            ambient_sound: AmbientSound::default(),
+            ambient_volume: 0.3,
+            ambient_file: String::new(),
+            webhook_url: String::new(),
+            state_socket_enabled: false,
+            stats_completed_only: false,
+            retention_days: 0,
+            weekday_goals: BTreeMap::new(),
+            profiles: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(backend: SettingsBackend, db: &Database) -> Self {
+        match backend {
+            SettingsBackend::File => Self::load_file(db),
+            SettingsBackend::Database => Self::load_db(db),
+        }
+    }
+
+    pub fn save(&self, backend: SettingsBackend, db: &Database) -> Result<(), Box<dyn Error>> {
+        match backend {
+            SettingsBackend::File => self.save_file(db)?,
+            SettingsBackend::Database => self.save_db(db)?,
+        }
+        Ok(())
+    }
+
+    /// Load settings from config.toml, falling back to defaults if missing or unreadable
+    fn load_file(db: &Database) -> Self {
+        let path = Self::get_config_path(db);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|e| {
+                eprintln!("Failed to parse {}: {}", path.display(), e);
+                Settings::default()
+            }),
+            Err(_) => Settings::default(),
+        }
+    }
+
+    fn save_file(&self, db: &Database) -> std::io::Result<()> {
+        let path = Self::get_config_path(db);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, contents)
+    }
+
+    /// One row per setting, each value stored as a TOML literal. A value that is missing,
+    /// unparseable or of the wrong type falls back to its default.
+    fn load_db(db: &Database) -> Self {
+        let defaults = Self::to_table(&Settings::default());
+        let mut table = toml::Table::new();
+
+        for (key, default) in defaults {
+            match db.get_setting(&key) {
+                Ok(Some(raw)) => match parse_value(&raw) {
+                    Some(value) if std::mem::discriminant(&value) == std::mem::discriminant(&default) => {
+                        table.insert(key, value);
+                    }
+                    _ => eprintln!("Ignoring invalid stored setting {} = {}", key, raw),
+                },
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to load setting {}: {}", key, e),
+            }
+        }
+
+        table.try_into().unwrap_or_else(|e| {
+            eprintln!("Failed to load settings from database: {}", e);
+            Settings::default()
+        })
+    }
+
+    fn save_db(&self, db: &Database) -> rusqlite::Result<()> {
+        for (key, value) in Self::to_table(self) {
+            db.set_setting(&key, &value.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn to_table(settings: &Settings) -> toml::Table {
+        toml::Table::try_from(settings).expect("Settings always serialize to a table")
+    }
+
+    /// Work session length in seconds; out-of-range values fall back to the default
+    pub fn work_seconds(&self) -> u64 {
+        minutes_or_default(self.work_minutes, DEFAULT_WORK_MINUTES) * 60
+    }
+
+    /// Short break length in seconds; out-of-range values fall back to the default
+    pub fn break_seconds(&self) -> u64 {
+        minutes_or_default(self.break_minutes, DEFAULT_BREAK_MINUTES) * 60
+    }
+
+    /// Long break length in seconds; out-of-range values fall back to the default
+    pub fn long_break_seconds(&self) -> u64 {
+        minutes_or_default(self.long_break_minutes, DEFAULT_LONG_BREAK_MINUTES) * 60
+    }
+
+    pub fn custom_sound_path(&self) -> Option<PathBuf> {
+        let path = self.sound_file.trim();
+        (!path.is_empty()).then(|| PathBuf::from(path))
+    }
+
+    /// Profile for a task label, if one is set up for it
+    pub fn profile_for(&self, label: &str) -> Option<&Profile> {
+        let label = label.trim();
+        if label.is_empty() {
+            return None;
+        }
+        self.profiles
+            .iter()
+            .find(|profile| profile.label.trim().eq_ignore_ascii_case(label))
+    }
+
+    /// What the ambient loop should play, or None when it's off or no file is picked
+    pub fn ambient_source(&self) -> Option<AmbientSource> {
+        match self.ambient_sound {
+            AmbientSound::Off => None,
+            AmbientSound::WhiteNoise => Some(AmbientSource::WhiteNoise),
+            AmbientSound::BrownNoise => Some(AmbientSource::BrownNoise),
+            AmbientSound::Custom => {
+                let path = self.ambient_file.trim();
+                (!path.is_empty()).then(|| AmbientSource::File(PathBuf::from(path)))
+            }
+        }
+    }
+
+    /// Focus goal for a day of the week, falling back to the single daily goal
+    pub fn goal_minutes_for(&self, weekday: Weekday) -> u32 {
+        self.weekday_goals
+            .get(&weekday_key(weekday))
+            .copied()
+            .unwrap_or(self.daily_goal_minutes)
+    }
+
+    /// Set a weekday's goal; matching the daily goal removes the override
+    pub fn set_goal_minutes_for(&mut self, weekday: Weekday, minutes: u32) {
+        if minutes == self.daily_goal_minutes {
+            self.weekday_goals.remove(&weekday_key(weekday));
+        } else {
+            self.weekday_goals.insert(weekday_key(weekday), minutes);
+        }
+    }
+
+    /// Smallest inner size of the main window; values under MIN_WINDOW_FLOOR are raised to it
+    pub fn min_window_size(&self) -> [f32; 2] {
+        let floored = |value: f32| if value.is_finite() { value.max(MIN_WINDOW_FLOOR) } else { MIN_WINDOW_FLOOR };
+        [floored(self.min_window_width), floored(self.min_window_height)]
+    }
+
+    /// Platforms without window transparency just draw the window opaque
+    pub fn wants_transparent_window(&self) -> bool {
+        self.window_opacity < 1.0
+    }
+
+    fn get_config_path(db: &Database) -> PathBuf {
+        db.data_dir().join("config.toml")
+    }
+}
+
+fn weekday_key(weekday: Weekday) -> String {
+    weekday.to_string().to_lowercase()
+}
+
+/// Parse a single TOML literal such as `25`, `true` or `"ocean"`
+fn parse_value(raw: &str) -> Option<toml::Value> {
+    let mut table: toml::Table = format!("value = {}", raw).parse().ok()?;
+    table.remove("value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_window_size_never_goes_below_the_floor() {
+        assert_eq!(Settings::default().min_window_size(), [200.0, 200.0]);
+
+        let settings = Settings {
+            min_window_width: 120.0,
+            min_window_height: f32::NAN,
+            ..Settings::default()
+        };
+        assert_eq!(settings.min_window_size(), [200.0, 200.0]);
+
+        let settings = Settings {
+            min_window_width: 320.0,
+            ..Settings::default()
+        };
+        assert_eq!(settings.min_window_size(), [320.0, 200.0]);
+    }
+}
@@ -0,0 +1,90 @@
+use eframe::egui;
+use chrono::Timelike;
+
+use crate::models::FocusContract;
+use crate::{notifications, PomodoroApp};
+
+pub(crate) const DEFAULT_CONTRACT_SESSIONS: u32 = 6;
+
+impl PomodoroApp {
+    /// Sessions committed to for the current day, if the user made a commitment
+    pub(crate) fn todays_commitment(&self) -> Option<u32> {
+        self.focus_contract
+            .filter(|contract| contract.day == self.db.current_day() && contract.sessions > 0)
+            .map(|contract| contract.sessions)
+    }
+
+    /// Ask once per day how many sessions the user commits to. "Not today" is remembered
+    /// for the day; closing the window only hides it until the next launch.
+    pub(crate) fn show_contract_prompt(&mut self, ctx: &egui::Context) {
+        let today = self.db.current_day();
+        let answered = self.focus_contract.is_some_and(|contract| contract.day == today);
+        if !self.settings.focus_contract_enabled || answered || self.contract_prompt_dismissed {
+            return;
+        }
+
+        let mut open = true;
+        let mut answer = None;
+        egui::Window::new("Today's commitment")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("I'll do");
+                    ui.add(egui::DragValue::new(&mut self.contract_input).range(1..=24));
+                    ui.label("sessions today");
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Commit").clicked() {
+                        answer = Some(self.contract_input);
+                    }
+                    if ui.button("Not today").clicked() {
+                        answer = Some(0);
+                    }
+                });
+            });
+
+        if let Some(sessions) = answer {
+            self.save_focus_contract(FocusContract {
+                day: today,
+                sessions,
+                recapped: false,
+            });
+        }
+        self.contract_prompt_dismissed = !open;
+    }
+
+    /// Compare committed and completed sessions once the recap hour has passed. Runs on
+    /// every frame, so a launch later the same evening still shows the recap.
+    pub(crate) fn check_contract_recap(&mut self) {
+        let Some(mut contract) = self.focus_contract else { return };
+        if contract.recapped
+            || contract.sessions == 0
+            || contract.day != self.db.current_day()
+            || self.clock.now_local().hour() < self.settings.contract_recap_hour
+        {
+            return;
+        }
+
+        let done = self.today_session_count;
+        let committed = contract.sessions as usize;
+        let body = if done >= committed {
+            format!("You committed to {} sessions and did {}. Well done!", committed, done)
+        } else {
+            format!("You committed to {} sessions and did {}.", committed, done)
+        };
+        notifications::send("Today's commitment", &body);
+
+        contract.recapped = true;
+        self.save_focus_contract(contract);
+    }
+
+    fn save_focus_contract(&mut self, contract: FocusContract) {
+        if let Err(e) = self.db.set_focus_contract(&contract) {
+            eprintln!("Failed to save focus contract: {}", e);
+        }
+        self.focus_contract = Some(contract);
+    }
+}
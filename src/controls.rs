@@ -0,0 +1,102 @@
+use eframe::egui;
+
+const BUTTON_HEIGHT: f32 = 36.0;
+
+/// Timer control buttons shown on the work and break screens
+#[derive(PartialEq, Clone, Copy)]
+pub enum ControlAction {
+    Start,
+    Pause,
+    Resume,
+    Restart,
+    Stop,
+    StartNewTimer,
+    SkipBreak,
+    DelayBreak,
+    TakeBreak,
+    EndEarly,
+    Interrupt,
+    Minimize,
+}
+
+impl ControlAction {
+    fn label(&self) -> &'static str {
+        match self {
+            ControlAction::Start => "Start",
+            ControlAction::Pause => "Pause",
+            ControlAction::Resume => "Resume",
+            ControlAction::Restart => "Restart",
+            ControlAction::Stop => "Stop",
+            ControlAction::StartNewTimer => "Start New Timer",
+            ControlAction::SkipBreak => "Skip Break",
+            ControlAction::DelayBreak => "Delay 1 min",
+            ControlAction::TakeBreak => "Take a Break",
+            ControlAction::EndEarly => "Take Break",
+            ControlAction::Interrupt => "Interrupt",
+            ControlAction::Minimize => "Minimize",
+        }
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            ControlAction::Start => Some("Start the timer (Space)"),
+            ControlAction::Pause => Some("Pause the timer (Space)"),
+            ControlAction::Resume => Some("Resume the timer (Space)"),
+            ControlAction::Restart => Some("Start this session over from the beginning (R)"),
+            ControlAction::Stop => Some("Discard this session and stop the timer"),
+            ControlAction::EndEarly => Some("End this session now and start the break"),
+            ControlAction::Interrupt => Some("Pause and record an interruption (I)"),
+            _ => None,
+        }
+    }
+}
+
+/// Equal-width buttons centered in a row, stacked vertically when the window is too narrow
+/// for the row. Returns the action whose button was clicked.
+pub fn control_buttons(
+    ui: &mut egui::Ui,
+    button_width: f32,
+    actions: &[ControlAction],
+) -> Option<ControlAction> {
+    if actions.is_empty() {
+        return None;
+    }
+
+    let count = actions.len() as f32;
+    let spacing = ui.spacing().item_spacing.x;
+    let total_width = button_width * count + spacing * (count - 1.0);
+    let mut clicked = None;
+
+    if ui.available_width() >= total_width {
+        ui.horizontal(|ui| {
+            ui.add_space(((ui.available_width() - total_width) / 2.0).max(0.0));
+            for action in actions {
+                if control_button(ui, *action, button_width) {
+                    clicked = Some(*action);
+                }
+            }
+        });
+    } else {
+        ui.vertical_centered(|ui| {
+            let width = button_width.min(ui.available_width());
+            for action in actions {
+                if control_button(ui, *action, width) {
+                    clicked = Some(*action);
+                }
+            }
+        });
+    }
+
+    clicked
+}
+
+fn control_button(ui: &mut egui::Ui, action: ControlAction, width: f32) -> bool {
+    let response = ui.add_sized(
+        [width, BUTTON_HEIGHT],
+        egui::Button::new(egui::RichText::new(action.label()).size(18.0)),
+    );
+    match action.hint() {
+        Some(hint) => response.on_hover_text(hint).clicked(),
+        None => response.clicked(),
+    }
+}
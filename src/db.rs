@@ -1,41 +1,194 @@
-use rusqlite::{Connection, Result};
-use std::path::PathBuf;
-use chrono::{Local, Timelike};
+use rusqlite::{Connection, OptionalExtension, Result, Row};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Timelike, Utc};
 
-use crate::models::WorkSession;
+use crate::clock::Clock;
+use crate::config::DayBoundary;
+use crate::models::{BreakSession, DailySummary, EndReason, FocusContract, WorkSession};
+use crate::stats::{longest_streak, streak_ending};
+use crate::window_geometry::WindowGeometry;
+
+type Migration = fn(&Database) -> Result<()>;
+
+/// Schema changes in order, each applied once in its own transaction and recorded as
+/// meta.schema_version. Append a new entry to change the schema; never edit a released one.
+/// Each step checks what's already there, since files from before the steps were numbered
+/// recorded other versions.
+const MIGRATIONS: &[(i64, Migration)] = &[
+    (1, Database::create_work_sessions),
+    (2, Database::add_notes),
+    (3, Database::create_settings),
+    (4, Database::add_goal_credit),
+    (5, Database::add_labels),
+    (6, Database::add_end_reasons),
+    (7, Database::create_skipped_breaks),
+    (8, Database::add_paused_seconds),
+    (9, Database::create_break_sessions),
+    (10, Database::create_daily_summaries),
+    (11, Database::add_interruptions),
+    (12, Database::add_break_lengths),
+    (13, Database::add_pause_count),
+    (14, Database::add_tags),
+];
+
+// The version written by the last migration
+const SCHEMA_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].0;
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5); // Wait for the other connection's write instead of failing
 
 pub struct Database {
     conn: Connection,
+    path: PathBuf,
+    data_dir: PathBuf, // Holds sessions.db and the other files the app keeps
+    clock: Arc<dyn Clock>,
+    day_boundary: DayBoundary,
+    day_start_hour: u32, // Local hour at which a new day begins (0 = midnight)
+    schema_warning: Option<String>, // Set when the file was written by a newer app
 }
 
 impl Database {
-    pub fn new() -> Result<Self> {
-        let db_path = Self::get_db_path();
+    pub fn new(clock: Arc<dyn Clock>) -> std::result::Result<Self, Box<dyn Error>> {
+        let db_path = Self::get_db_path()?;
+        let data_dir = db_path.parent().map(Path::to_path_buf).unwrap_or_default();
         
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent)
-                .expect("Failed to create application data directory");
-        }
+        // Create the data directory if it doesn't exist
+        std::fs::create_dir_all(&data_dir)?;
         
-        let conn = Connection::open(db_path)?;
+        Ok(Self::open(&db_path, data_dir, clock)?)
+    }
+    
+    /// Open the file at db_path and bring it up to the current schema
+    fn open(db_path: &Path, data_dir: PathBuf, clock: Arc<dyn Clock>) -> Result<Self> {
+        let conn = Self::connect(db_path)?;
+        // Lets the session writer's connection commit while this one reads
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
         
-        let db = Database { conn };
-        db.initialize()?;
+        let mut db = Database {
+            conn,
+            path: db_path.to_path_buf(),
+            data_dir,
+            clock,
+            day_boundary: DayBoundary::default(),
+            day_start_hour: 0,
+            schema_warning: None,
+        };
+        db.migrate()?;
         
         Ok(db)
     }
     
-    fn get_db_path() -> PathBuf {
-        // Hard-coded for macOS, but modular for future expansion
-        let home = std::env::var("HOME").expect("HOME environment variable not set");
-        let mut path = PathBuf::from(home);
-        path.push("Library/Application Support/pocket_flow");
-        path.push("sessions.db");
-        path
+    /// A scratch database that lives only as long as the connection
+    #[cfg(test)]
+    pub fn open_in_memory(clock: Arc<dyn Clock>) -> Result<Self> {
+        Self::open(Path::new(":memory:"), PathBuf::new(), clock)
+    }
+    
+    /// A database kept in a file of the test's choosing, for checks that reopen it
+    #[cfg(test)]
+    pub fn open_at(db_path: &Path, clock: Arc<dyn Clock>) -> Result<Self> {
+        let data_dir = db_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Self::open(db_path, data_dir, clock)
+    }
+    
+    /// A second connection to the same file, for use on another thread. The schema is
+    /// already current, so nothing is migrated. An in-memory database has no file to share.
+    pub fn reopen(&self) -> Result<Self> {
+        if self.path == Path::new(":memory:") {
+            return Err(rusqlite::Error::InvalidPath(self.path.clone()));
+        }
+        Ok(Database {
+            conn: Self::connect(&self.path)?,
+            path: self.path.clone(),
+            data_dir: self.data_dir.clone(),
+            clock: self.clock.clone(),
+            day_boundary: self.day_boundary,
+            day_start_hour: self.day_start_hour,
+            schema_warning: None,
+        })
+    }
+    
+    fn connect(path: &Path) -> Result<Connection> {
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(conn)
+    }
+    
+    /// The platform's per-user data folder: ~/Library/Application Support/pocket_flow on
+    /// macOS, $XDG_DATA_HOME/pocket_flow on Linux and %APPDATA%\pocket_flow on Windows
+    fn get_db_path() -> io::Result<PathBuf> {
+        let data_dir = dirs::data_dir().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "No home directory to keep sessions in")
+        })?;
+        Ok(data_dir.join("pocket_flow").join("sessions.db"))
+    }
+    
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+    
+    pub fn set_day_boundary(&mut self, day_boundary: DayBoundary) {
+        self.day_boundary = day_boundary;
+    }
+    
+    pub fn set_day_start_hour(&mut self, hour: u32) {
+        self.day_start_hour = hour.min(23);
+    }
+    
+    /// The day "now" belongs to: before day_start_hour it still counts as the previous day
+    pub fn current_day(&self) -> NaiveDate {
+        self.day_of(self.clock.now_utc())
     }
     
-    fn initialize(&self) -> Result<()> {
+    /// Log the schema/app versions that last wrote the file, then run the migrations it hasn't
+    /// had yet. A file from a newer app is left untouched and flagged instead.
+    fn migrate(&mut self) -> Result<()> {
+        // Holds the version, so it has to exist before anything else
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        let stored_schema = self.get_meta("schema_version")?.and_then(|v| v.parse::<i64>().ok());
+        let stored_app = self.get_meta("app_version")?;
+        eprintln!(
+            "Database schema version {} (last written by pocket_flow {})",
+            stored_schema.map_or("none".to_string(), |v| v.to_string()),
+            stored_app.as_deref().unwrap_or("unknown"),
+        );
+        
+        if let Some(stored) = stored_schema.filter(|v| *v > SCHEMA_VERSION) {
+            let warning = format!(
+                "Your data was written by a newer Pocket Flow ({}, schema {}). Please update the app.",
+                stored_app.as_deref().unwrap_or("unknown version"),
+                stored,
+            );
+            eprintln!("{}", warning);
+            self.schema_warning = Some(warning);
+            return Ok(());
+        }
+        
+        let current = stored_schema.unwrap_or(0);
+        for (version, migration) in MIGRATIONS.iter().filter(|(version, _)| *version > current) {
+            let tx = self.conn.unchecked_transaction()?;
+            migration(self)?;
+            self.set_meta("schema_version", &version.to_string())?;
+            tx.commit()?;
+            eprintln!("Migrated database to schema version {}", version);
+        }
+        
+        self.set_meta("app_version", env!("CARGO_PKG_VERSION"))?;
+        Ok(())
+    }
+    
+    /// The table as the first release shipped it
+    fn create_work_sessions(&self) -> Result<()> {
         self.conn.execute(
             "CREATE TABLE IF NOT EXISTS work_sessions (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -45,42 +198,1208 @@ impl Database {
             )",
             [],
         )?;
+        Ok(())
+    }
+    
+    fn add_notes(&self) -> Result<()> {
+        self.add_column_if_missing("work_sessions", "note", "TEXT")?;
+        Ok(())
+    }
+    
+    /// Settings kept here with --settings-in-db instead of in config.toml
+    fn create_settings(&self) -> Result<()> {
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )?;
+        Ok(())
+    }
+    
+    /// Sessions from before Settings::goal_requires_break all counted
+    fn add_goal_credit(&self) -> Result<()> {
+        self.add_column_if_missing("work_sessions", "counts_toward_goal", "INTEGER NOT NULL DEFAULT 1")?;
+        Ok(())
+    }
+    
+    fn add_labels(&self) -> Result<()> {
+        self.add_column_if_missing("work_sessions", "label", "TEXT")?;
+        Ok(())
+    }
+    
+    /// Rows from before end reasons were tracked count as completed
+    fn add_end_reasons(&self) -> Result<()> {
+        self.add_column_if_missing("work_sessions", "end_reason", "TEXT NOT NULL DEFAULT 'completed'")?;
+        Ok(())
+    }
+    
+    /// Superseded by break_sessions in version 9
+    fn create_skipped_breaks(&self) -> Result<()> {
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS skipped_breaks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skipped_at TEXT NOT NULL,
+                long_break INTEGER NOT NULL,
+                reason TEXT
+            )",
+            [],
+        )?;
+        Ok(())
+    }
+    
+    /// Sessions from before pause time was tracked read as never paused
+    fn add_paused_seconds(&self) -> Result<()> {
+        self.add_column_if_missing("work_sessions", "paused_seconds", "INTEGER NOT NULL DEFAULT 0")?;
+        Ok(())
+    }
+    
+    /// One row per break that ran out or was skipped, taking over the skips recorded so far
+    fn create_break_sessions(&self) -> Result<()> {
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS break_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ended_at TEXT NOT NULL,
+                long_break INTEGER NOT NULL,
+                skipped INTEGER NOT NULL,
+                skip_reason TEXT
+            )",
+            [],
+        )?;
+        self.migrate_skipped_breaks()
+    }
+    
+    /// Cache of per-day totals, rebuilt from work_sessions whenever a row is missing
+    fn create_daily_summaries(&self) -> Result<()> {
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS daily_summaries (
+                day TEXT PRIMARY KEY,
+                sessions INTEGER NOT NULL,
+                focus_seconds INTEGER NOT NULL,
+                goal_seconds INTEGER NOT NULL,
+                paused_seconds INTEGER NOT NULL,
+                interruptions INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        Ok(())
+    }
+    
+    fn add_interruptions(&self) -> Result<()> {
+        // Summaries from before counted sessions cut off by quitting as interruptions
+        if self.add_column_if_missing("work_sessions", "interruptions", "INTEGER NOT NULL DEFAULT 0")? {
+            self.clear_summaries()?;
+        }
+        Ok(())
+    }
+    
+    /// Rows from before these were kept have no start and count as zero length
+    fn add_break_lengths(&self) -> Result<()> {
+        self.add_column_if_missing("break_sessions", "started_at", "TEXT")?;
+        self.add_column_if_missing("break_sessions", "duration_seconds", "INTEGER NOT NULL DEFAULT 0")?;
+        Ok(())
+    }
+    
+    /// Sessions from before pauses were counted read as never paused
+    fn add_pause_count(&self) -> Result<()> {
+        self.add_column_if_missing("work_sessions", "pause_count", "INTEGER NOT NULL DEFAULT 0")?;
+        Ok(())
+    }
+    
+    fn add_tags(&self) -> Result<()> {
+        self.add_column_if_missing("work_sessions", "tag", "TEXT")?;
+        Ok(())
+    }
+    
+    /// Skips were kept in their own table before taken breaks were recorded too.
+    /// Runs inside the migration's transaction.
+    fn migrate_skipped_breaks(&self) -> Result<()> {
+        let exists: bool = self.conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'skipped_breaks'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Ok(());
+        }
         
+        self.conn.execute(
+            "INSERT INTO break_sessions (ended_at, long_break, skipped, skip_reason)
+             SELECT skipped_at, long_break, 1, reason FROM skipped_breaks",
+            [],
+        )?;
+        self.conn.execute("DROP TABLE skipped_breaks", [])?;
         Ok(())
     }
     
-    pub fn save_work_session(&self, session: &WorkSession) -> Result<()> {
+    pub fn schema_warning(&self) -> Option<&str> {
+        self.schema_warning.as_deref()
+    }
+    
+    /// Position in the Pomodoro cycle, kept in meta so long breaks stay on schedule across restarts
+    pub fn get_cycle_position(&self) -> Result<usize> {
+        Ok(self
+            .get_meta("cycle_position")?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0))
+    }
+    
+    pub fn set_cycle_position(&self, position: usize) -> Result<()> {
+        self.set_meta("cycle_position", &position.to_string())
+    }
+    
+    /// Remaining seconds of a work session that was running or paused when the app quit
+    pub fn set_interrupted_work(&self, remaining_seconds: Option<u64>) -> Result<()> {
+        match remaining_seconds {
+            Some(seconds) => self.set_meta("interrupted_work_remaining", &seconds.to_string()),
+            None => {
+                self.conn.execute("DELETE FROM meta WHERE key = 'interrupted_work_remaining'", [])?;
+                Ok(())
+            }
+        }
+    }
+    
+    /// Read and clear the work session left over from the last run
+    pub fn take_interrupted_work(&self) -> Result<Option<u64>> {
+        let remaining = self
+            .get_meta("interrupted_work_remaining")?
+            .and_then(|v| v.parse().ok());
+        self.set_interrupted_work(None)?;
+        Ok(remaining)
+    }
+    
+    /// The latest day's focus contract, if one was ever made
+    pub fn get_focus_contract(&self) -> Result<Option<FocusContract>> {
+        let Some(day) = self
+            .get_meta("contract_day")?
+            .and_then(|v| NaiveDate::parse_from_str(&v, "%Y-%m-%d").ok())
+        else {
+            return Ok(None);
+        };
+        let sessions = self.get_meta("contract_sessions")?.and_then(|v| v.parse().ok()).unwrap_or(0);
+        let recapped = self.get_meta("contract_recapped")?.as_deref() == Some("1");
+        Ok(Some(FocusContract { day, sessions, recapped }))
+    }
+    
+    pub fn set_focus_contract(&self, contract: &FocusContract) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.set_meta("contract_day", &contract.day.format("%Y-%m-%d").to_string())?;
+        self.set_meta("contract_sessions", &contract.sessions.to_string())?;
+        self.set_meta("contract_recapped", if contract.recapped { "1" } else { "0" })?;
+        tx.commit()
+    }
+    
+    /// Window position and size from the last quit, see window_geometry.rs
+    pub fn get_window_geometry(&self) -> Result<Option<WindowGeometry>> {
+        Ok(self.get_meta("window_geometry")?.and_then(|v| WindowGeometry::from_meta(&v)))
+    }
+    
+    pub fn set_window_geometry(&self, geometry: &WindowGeometry) -> Result<()> {
+        self.set_meta("window_geometry", &geometry.to_meta())
+    }
+    
+    /// Raw stored value of a setting, for the database settings backend
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| row.get(0))
+            .optional()
+    }
+    
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO work_sessions (started_at, completed_at, duration_seconds)
-             VALUES (?1, ?2, ?3)",
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [key, value],
+        )?;
+        Ok(())
+    }
+    
+    fn get_meta(&self, key: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| row.get(0))
+            .optional()
+    }
+    
+    fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [key, value],
+        )?;
+        Ok(())
+    }
+    
+    /// Returns whether the column had to be added
+    fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> Result<bool> {
+        let exists: bool = self.conn.query_row(
+            &format!("SELECT COUNT(*) > 0 FROM pragma_table_info('{table}') WHERE name = ?1"),
+            [column],
+            |row| row.get(0),
+        )?;
+        
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+                [],
+            )?;
+        }
+        
+        Ok(!exists)
+    }
+    
+    /// Returns the id of the new row. Written from the session writer's connection, so the
+    /// day's summary is left to invalidate_session_day once the UI hears back.
+    pub fn insert_work_session(&self, session: &WorkSession) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO work_sessions (started_at, completed_at, duration_seconds, note, counts_toward_goal, label, end_reason, paused_seconds, interruptions, pause_count, tag)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             (
                 session.started_at.to_rfc3339(),
                 session.completed_at.to_rfc3339(),
                 session.duration_seconds,
+                &session.note,
+                session.counts_toward_goal,
+                &session.label,
+                session.end_reason.as_str(),
+                session.paused_seconds,
+                session.interruptions,
+                session.pause_count,
+                &session.tag,
             ),
         )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+    
+    /// Drop the stored summary of the day a newly saved session belongs to
+    pub fn invalidate_session_day(&self, session: &WorkSession) -> Result<()> {
+        self.invalidate_summary(self.session_day(session))
+    }
+    
+    /// Let a session held back by the break-taken rule count toward the goal
+    pub fn credit_session_to_goal(&self, id: i64) -> Result<()> {
+        self.invalidate_session_summary(id)?;
+        self.conn.execute(
+            "UPDATE work_sessions SET counts_toward_goal = 1 WHERE id = ?1",
+            [id],
+        )?;
         
         Ok(())
     }
     
-    pub fn get_sessions_count_for_today(&self) -> Result<usize> {
-        // Get start of today in local timezone
-        let now = Local::now();
-        let start_of_day = now
-            .with_hour(0)
-            .and_then(|d| d.with_minute(0))
-            .and_then(|d| d.with_second(0))
-            .and_then(|d| d.with_nanosecond(0))
-            .expect("Failed to calculate start of day");
+    /// Insert sessions in one transaction, skipping any whose start time is already stored.
+    /// Returns how many were inserted.
+    pub fn import_sessions(&self, sessions: &[WorkSession]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut inserted = 0;
         
-        let start_of_day_str = start_of_day.to_rfc3339();
+        for session in sessions {
+            inserted += tx.execute(
+                "INSERT INTO work_sessions (started_at, completed_at, duration_seconds, note, label, end_reason, tag)
+                 SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7
+                 WHERE NOT EXISTS (SELECT 1 FROM work_sessions WHERE started_at = ?1)",
+                (
+                    session.started_at.to_rfc3339(),
+                    session.completed_at.to_rfc3339(),
+                    session.duration_seconds,
+                    &session.note,
+                    &session.label,
+                    session.end_reason.as_str(),
+                    &session.tag,
+                ),
+            )?;
+        }
         
-        let count: usize = self.conn.query_row(
-            "SELECT COUNT(*) FROM work_sessions WHERE started_at >= ?1",
-            [start_of_day_str],
-            |row| row.get(0),
+        tx.commit()?;
+        self.clear_summaries()?;
+        Ok(inserted)
+    }
+    
+    pub fn save_break_session(&self, session: &BreakSession) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO break_sessions (started_at, ended_at, duration_seconds, long_break, skipped, skip_reason)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            (
+                session.started_at.to_rfc3339(),
+                session.ended_at.to_rfc3339(),
+                session.duration_seconds,
+                session.long_break,
+                session.skipped,
+                &session.skip_reason,
+            ),
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Most recent breaks first
+    pub fn get_recent_break_sessions(&self, limit: usize) -> Result<Vec<BreakSession>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(started_at, ended_at), ended_at, duration_seconds, long_break, skipped, skip_reason
+             FROM break_sessions ORDER BY ended_at DESC LIMIT ?1",
+        )?;
+        let sessions = stmt.query_map([limit as i64], |row| {
+            Ok(BreakSession {
+                started_at: Self::parse_timestamp(row, 0)?,
+                ended_at: Self::parse_timestamp(row, 1)?,
+                duration_seconds: row.get(2)?,
+                long_break: row.get(3)?,
+                skipped: row.get(4)?,
+                skip_reason: row.get(5)?,
+            })
+        })?;
+        sessions.collect()
+    }
+    
+    /// Breaks taken in full and breaks skipped during the days from first to last (inclusive)
+    pub fn get_break_counts(&self, first: NaiveDate, last: NaiveDate) -> Result<(usize, usize)> {
+        let (start, _) = self.day_bounds(first);
+        let (_, end) = self.day_bounds(last);
+        
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(skipped = 0), 0), COALESCE(SUM(skipped = 1), 0) FROM break_sessions
+             WHERE ended_at >= ?1 AND ended_at < ?2",
+            [start.to_rfc3339(), end.to_rfc3339()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+    
+    /// Set or clear (with None or an empty string) the note on a saved session.
+    /// Only the note column changes, so timestamps and counts are untouched.
+    pub fn update_session_note(&self, id: i64, note: Option<&str>) -> Result<()> {
+        let note = note.map(str::trim).filter(|n| !n.is_empty());
+        self.conn.execute(
+            "UPDATE work_sessions SET note = ?1 WHERE id = ?2",
+            (note, id),
         )?;
         
+        Ok(())
+    }
+    
+    /// Sessions whose stored duration is non-positive, longer than max_seconds, or
+    /// disagrees with their own timestamps, e.g. after a clock correction mid-session
+    pub fn get_suspect_sessions(&self, max_seconds: i64) -> Result<Vec<WorkSession>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, started_at, completed_at, duration_seconds, note, counts_toward_goal, label, end_reason, paused_seconds, interruptions, pause_count, tag
+             FROM work_sessions
+             WHERE duration_seconds <= 0 OR duration_seconds > ?1 OR completed_at < started_at
+             ORDER BY started_at",
+        )?;
+        let sessions = stmt.query_map([max_seconds], Self::session_from_row)?;
+        sessions.collect()
+    }
+    
+    pub fn set_session_duration(&self, id: i64, duration_seconds: i64) -> Result<()> {
+        self.invalidate_session_summary(id)?;
+        self.conn.execute(
+            "UPDATE work_sessions SET duration_seconds = ?1 WHERE id = ?2",
+            (duration_seconds, id),
+        )?;
+        
+        Ok(())
+    }
+    
+    pub fn set_end_reason(&self, id: i64, reason: EndReason) -> Result<()> {
+        self.invalidate_session_summary(id)?;
+        self.conn.execute(
+            "UPDATE work_sessions SET end_reason = ?1 WHERE id = ?2",
+            (reason.as_str(), id),
+        )?;
+        
+        Ok(())
+    }
+    
+    pub fn delete_session(&self, id: i64) -> Result<()> {
+        self.invalidate_session_summary(id)?;
+        self.conn.execute("DELETE FROM work_sessions WHERE id = ?1", [id])?;
+        
+        Ok(())
+    }
+    
+    /// Delete every work and break session belonging to a day before `day`, in one
+    /// transaction. Returns how many work sessions were removed.
+    pub fn prune_before(&self, day: NaiveDate) -> Result<usize> {
+        let cutoff = self.day_start(day);
+        let column = self.day_boundary.column();
+        
+        let tx = self.conn.unchecked_transaction()?;
+        let removed = tx.execute(
+            &format!("DELETE FROM work_sessions WHERE {column} < ?1"),
+            [cutoff.to_rfc3339()],
+        )?;
+        // Breaks are bucketed by when they ended, as in get_break_counts
+        tx.execute("DELETE FROM break_sessions WHERE ended_at < ?1", [cutoff.to_rfc3339()])?;
+        tx.commit()?;
+        self.clear_summaries()?;
+        
+        Ok(removed)
+    }
+    
+    /// Delete every work and break session, in one transaction, then vacuum to give the space
+    /// back. Settings and the rest of the meta table are kept.
+    pub fn clear_all_sessions(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM work_sessions", [])?;
+        tx.execute("DELETE FROM break_sessions", [])?;
+        tx.execute("DELETE FROM daily_summaries", [])?;
+        tx.commit()?;
+        
+        // Only frees space; the sessions are already gone if this fails
+        if let Err(e) = self.conn.execute_batch("VACUUM") {
+            eprintln!("Failed to vacuum database: {}", e);
+        }
+        Ok(())
+    }
+    
+    /// Labels used so far, most recently used first
+    pub fn get_distinct_labels(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT label FROM work_sessions WHERE label IS NOT NULL
+             GROUP BY label ORDER BY MAX(started_at) DESC",
+        )?;
+        let labels = stmt.query_map([], |row| row.get(0))?;
+        labels.collect()
+    }
+    
+    /// Consecutive days with a session, ending today or, while today has none yet, yesterday.
+    /// Days follow the same shifted boundary as the rest of the stats.
+    pub fn get_current_streak(&self) -> Result<u32> {
+        Ok(streak_ending(&self.session_days()?, self.current_day()))
+    }
+    
+    pub fn get_longest_streak(&self) -> Result<u32> {
+        Ok(longest_streak(&self.session_days()?))
+    }
+    
+    /// Every day that had a session, oldest first, without repeats
+    fn session_days(&self) -> Result<Vec<NaiveDate>> {
+        let column = self.day_boundary.column();
+        let mut stmt = self.conn.prepare(&format!("SELECT {column} FROM work_sessions ORDER BY {column}"))?;
+        let mut days: Vec<NaiveDate> = stmt
+            .query_map([], |row| Self::parse_timestamp(row, 0))?
+            .map(|timestamp| timestamp.map(|timestamp| self.day_of(timestamp)))
+            .collect::<Result<_>>()?;
+        // Sorted in UTC; a time zone change can leave local days slightly out of order
+        days.sort();
+        days.dedup();
+        Ok(days)
+    }
+    
+    /// All-time focus seconds per label, largest first. Unlabeled sessions are grouped under None.
+    pub fn get_focus_seconds_by_label(&self) -> Result<Vec<(Option<String>, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT label, CAST(TOTAL(duration_seconds) AS INTEGER) AS seconds FROM work_sessions
+             GROUP BY label ORDER BY seconds DESC",
+        )?;
+        let totals = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        totals.collect()
+    }
+    
+    /// All-time focus seconds per project tag, largest first. Untagged sessions are grouped under None.
+    pub fn get_focus_seconds_by_tag(&self) -> Result<Vec<(Option<String>, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tag, CAST(TOTAL(duration_seconds) AS INTEGER) AS seconds FROM work_sessions
+             GROUP BY tag ORDER BY seconds DESC",
+        )?;
+        let totals = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        totals.collect()
+    }
+    
+    /// All-time focus seconds by local hour of day, index 0 = midnight. A session that runs past
+    /// the hour is attributed entirely to the hour it started in.
+    pub fn get_focus_by_hour(&self) -> Result<[i64; 24]> {
+        let mut stmt = self.conn.prepare("SELECT started_at, duration_seconds FROM work_sessions")?;
+        let mut rows = stmt.query([])?;
+        let mut hours = [0i64; 24];
+        while let Some(row) = rows.next()? {
+            // Bucketed here rather than in SQL, which can't convert to local time across DST
+            let hour = Self::parse_timestamp(row, 0)?.with_timezone(&Local).hour() as usize;
+            hours[hour] = hours[hour].saturating_add(row.get(1)?);
+        }
+        Ok(hours)
+    }
+    
+    /// Most recent sessions first, skipping the newest `offset` for paging further back
+    pub fn get_recent_sessions(&self, limit: usize, offset: usize) -> Result<Vec<WorkSession>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, started_at, completed_at, duration_seconds, note, counts_toward_goal, label, end_reason, paused_seconds, interruptions, pause_count, tag
+             FROM work_sessions ORDER BY started_at DESC, id DESC LIMIT ?1 OFFSET ?2",
+        )?;
+        let sessions = stmt.query_map([limit as i64, offset as i64], Self::session_from_row)?;
+        sessions.collect()
+    }
+    
+    /// Write every session as id,started_at,completed_at,duration_seconds, oldest first, creating
+    /// or truncating the file. Timestamps are copied exactly as stored.
+    pub fn export_sessions_csv(&self, path: &Path) -> std::result::Result<usize, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, started_at, completed_at, duration_seconds, note, label, tag FROM work_sessions ORDER BY started_at",
+        )?;
+        let mut rows = stmt.query([])?;
+        
+        let mut writer = csv::Writer::from_path(path)?;
+        writer.write_record(["id", "started_at", "completed_at", "duration_seconds", "note", "label", "tag"])?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let started_at: String = row.get(1)?;
+            let completed_at: String = row.get(2)?;
+            let duration_seconds: i64 = row.get(3)?;
+            let note: Option<String> = row.get(4)?;
+            let label: Option<String> = row.get(5)?;
+            let tag: Option<String> = row.get(6)?;
+            writer.write_record([
+                id.to_string(),
+                started_at,
+                completed_at,
+                duration_seconds.to_string(),
+                note.unwrap_or_default(),
+                label.unwrap_or_default(),
+                tag.unwrap_or_default(),
+            ])?;
+            count += 1;
+        }
+        writer.flush()?;
+        
         Ok(count)
     }
+    
+    /// Restore a backup written by export_sessions_csv, skipping sessions whose start time is
+    /// already stored. A malformed row fails the whole import, naming its line.
+    pub fn import_sessions_csv(&self, path: &Path) -> std::result::Result<usize, Box<dyn Error>> {
+        let mut reader = csv::Reader::from_path(path)?;
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+        for name in ["started_at", "completed_at", "duration_seconds"] {
+            if column(name).is_none() {
+                return Err(format!("Backup has no {} column", name).into());
+            }
+        }
+        
+        let mut sessions = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let line = record.position().map_or(sessions.len() as u64 + 2, |p| p.line());
+            let field = |name: &str| column(name).and_then(|i| record.get(i)).map_or("", str::trim);
+            let timestamp = |name: &str| {
+                DateTime::parse_from_rfc3339(field(name))
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| format!("Line {}: {} {:?} isn't an RFC 3339 timestamp", line, name, field(name)))
+            };
+            
+            let started_at = timestamp("started_at")?;
+            let completed_at = timestamp("completed_at")?;
+            if completed_at < started_at {
+                return Err(format!("Line {}: completed_at is before started_at", line).into());
+            }
+            let mut session = WorkSession::new(started_at, completed_at);
+            // Paused time is left out of the saved duration, so it can be shorter than the span
+            session.duration_seconds = match field("duration_seconds").parse::<i64>() {
+                Ok(seconds) if (0..=session.duration_seconds).contains(&seconds) => seconds,
+                _ => {
+                    return Err(format!(
+                        "Line {}: duration_seconds {:?} isn't a number of seconds within the session",
+                        line,
+                        field("duration_seconds")
+                    )
+                    .into())
+                }
+            };
+            session.note = Some(field("note")).filter(|n| !n.is_empty()).map(str::to_string);
+            session.label = Some(field("label")).filter(|l| !l.is_empty()).map(str::to_string);
+            session.tag = Some(field("tag")).filter(|t| !t.is_empty()).map(str::to_string);
+            sessions.push(session);
+        }
+        
+        Ok(self.import_sessions(&sessions)?)
+    }
+    
+    /// Sessions belonging to the days from first to last (inclusive), in the order they happened
+    pub fn get_sessions_between(&self, first: NaiveDate, last: NaiveDate) -> Result<Vec<WorkSession>> {
+        let (start, _) = self.day_bounds(first);
+        let (_, end) = self.day_bounds(last);
+        let column = self.day_boundary.column();
+        
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, started_at, completed_at, duration_seconds, note, counts_toward_goal, label, end_reason, paused_seconds, interruptions, pause_count, tag
+             FROM work_sessions WHERE {column} >= ?1 AND {column} < ?2 ORDER BY started_at"
+        ))?;
+        let sessions = stmt.query_map([start.to_rfc3339(), end.to_rfc3339()], Self::session_from_row)?;
+        sessions.collect()
+    }
+    
+    /// Session count and focus seconds for each of the last `days` days, today last. Days
+    /// without a session are included as zero, so the range has no gaps.
+    pub fn get_sessions_per_day(&self, days: u32) -> Result<Vec<(NaiveDate, usize, i64)>> {
+        if days == 0 {
+            return Ok(Vec::new());
+        }
+        let today = self.current_day();
+        let first = today - chrono::Duration::days(days as i64 - 1);
+        let mut totals: Vec<(NaiveDate, usize, i64)> = first
+            .iter_days()
+            .take(days as usize)
+            .map(|day| (day, 0, 0))
+            .collect();
+        
+        for session in self.get_sessions_between(first, today)? {
+            let offset = (self.session_day(&session) - first).num_days();
+            if let Some((_, sessions, seconds)) = usize::try_from(offset).ok().and_then(|i| totals.get_mut(i)) {
+                *sessions += 1;
+                *seconds = seconds.saturating_add(session.duration_seconds);
+            }
+        }
+        Ok(totals)
+    }
+    
+    pub fn get_sessions_for_day(&self, day: NaiveDate) -> Result<Vec<WorkSession>> {
+        self.get_sessions_between(day, day)
+    }
+    
+    /// UTC range [start, end) of a day, for placing sessions on a timeline
+    pub fn get_day_bounds(&self, day: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        self.day_bounds(day)
+    }
+    
+    fn session_from_row(row: &Row) -> Result<WorkSession> {
+        Ok(WorkSession {
+            id: Some(row.get(0)?),
+            started_at: Self::parse_timestamp(row, 1)?,
+            completed_at: Self::parse_timestamp(row, 2)?,
+            duration_seconds: row.get(3)?,
+            note: row.get(4)?,
+            counts_toward_goal: row.get(5)?,
+            label: row.get(6)?,
+            end_reason: EndReason::parse(&row.get::<_, String>(7)?),
+            paused_seconds: row.get(8)?,
+            interruptions: row.get(9)?,
+            pause_count: row.get(10)?,
+            tag: row.get(11)?,
+        })
+    }
+    
+    fn parse_timestamp(row: &Row, idx: usize) -> Result<DateTime<Utc>> {
+        let value: String = row.get(idx)?;
+        DateTime::parse_from_rfc3339(&value)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+            })
+    }
+    
+    /// Session count and focus seconds for the current day
+    pub fn get_today_totals(&self) -> Result<(usize, i64)> {
+        let (start, end) = self.day_bounds(self.current_day());
+        let column = self.day_boundary.column();
+        
+        // TOTAL sums as a float and the cast saturates, where SUM would fail on overflow
+        self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*), CAST(TOTAL(duration_seconds) AS INTEGER) FROM work_sessions
+                 WHERE {column} >= ?1 AND {column} < ?2"
+            ),
+            [start.to_rfc3339(), end.to_rfc3339()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+    
+    /// Today's sessions that count toward the goal, leaving out any still waiting for their
+    /// break, see Settings::goal_requires_break
+    pub fn get_today_goal_count(&self) -> Result<usize> {
+        let (start, end) = self.day_bounds(self.current_day());
+        let column = self.day_boundary.column();
+        
+        self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM work_sessions
+                 WHERE counts_toward_goal = 1 AND {column} >= ?1 AND {column} < ?2"
+            ),
+            [start.to_rfc3339(), end.to_rfc3339()],
+            |row| row.get(0),
+        )
+    }
+    
+    /// Focus seconds across every saved session, for the all-time headline
+    pub fn get_total_focus_seconds(&self) -> Result<i64> {
+        self.conn.query_row(
+            "SELECT CAST(TOTAL(duration_seconds) AS INTEGER) FROM work_sessions",
+            [],
+            |row| row.get(0),
+        )
+    }
+    
+    pub fn get_total_session_count(&self) -> Result<usize> {
+        self.conn.query_row("SELECT COUNT(*) FROM work_sessions", [], |row| row.get(0))
+    }
+    
+    /// One summary per day from first to last (inclusive), computing and storing any that
+    /// are missing. Days after today are returned empty and never stored.
+    pub fn get_daily_summaries(&self, first: NaiveDate, last: NaiveDate) -> Result<Vec<DailySummary>> {
+        self.check_summary_rules()?;
+        let today = self.current_day();
+        
+        let mut stored: HashMap<NaiveDate, DailySummary> = HashMap::new();
+        {
+            let mut stmt = self.conn.prepare(
+                "SELECT day, sessions, focus_seconds, goal_seconds, paused_seconds, interruptions
+                 FROM daily_summaries WHERE day >= ?1 AND day <= ?2",
+            )?;
+            let rows = stmt.query_map(
+                [first.format("%Y-%m-%d").to_string(), last.format("%Y-%m-%d").to_string()],
+                |row| {
+                    let day: String = row.get(0)?;
+                    let day = NaiveDate::parse_from_str(&day, "%Y-%m-%d").map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+                    })?;
+                    Ok(DailySummary {
+                        day,
+                        sessions: row.get(1)?,
+                        focus_seconds: row.get(2)?,
+                        goal_seconds: row.get(3)?,
+                        paused_seconds: row.get(4)?,
+                        interruptions: row.get(5)?,
+                    })
+                },
+            )?;
+            for summary in rows {
+                let summary = summary?;
+                stored.insert(summary.day, summary);
+            }
+        }
+        
+        let days: Vec<NaiveDate> = first.iter_days().take_while(|day| *day <= last).collect();
+        let missing: Vec<NaiveDate> = days
+            .iter()
+            .copied()
+            .filter(|day| *day <= today && !stored.contains_key(day))
+            .collect();
+        if let (Some(&from), Some(&to)) = (missing.first(), missing.last()) {
+            let mut computed: HashMap<NaiveDate, DailySummary> =
+                missing.iter().map(|day| (*day, Self::empty_summary(*day))).collect();
+            for session in self.get_sessions_between(from, to)? {
+                if let Some(summary) = computed.get_mut(&self.session_day(&session)) {
+                    summary.sessions += 1;
+                    summary.focus_seconds = summary.focus_seconds.saturating_add(session.duration_seconds);
+                    if session.counts_toward_goal {
+                        summary.goal_seconds = summary.goal_seconds.saturating_add(session.duration_seconds);
+                    }
+                    summary.paused_seconds = summary.paused_seconds.saturating_add(session.paused_seconds);
+                    summary.interruptions += session.interruptions as usize;
+                }
+            }
+            
+            let tx = self.conn.unchecked_transaction()?;
+            for summary in computed.values() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO daily_summaries
+                     (day, sessions, focus_seconds, goal_seconds, paused_seconds, interruptions)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    (
+                        summary.day.format("%Y-%m-%d").to_string(),
+                        summary.sessions,
+                        summary.focus_seconds,
+                        summary.goal_seconds,
+                        summary.paused_seconds,
+                        summary.interruptions,
+                    ),
+                )?;
+            }
+            tx.commit()?;
+            stored.extend(computed);
+        }
+        
+        Ok(days
+            .into_iter()
+            .map(|day| stored.remove(&day).unwrap_or_else(|| Self::empty_summary(day)))
+            .collect())
+    }
+    
+    fn empty_summary(day: NaiveDate) -> DailySummary {
+        DailySummary {
+            day,
+            sessions: 0,
+            focus_seconds: 0,
+            goal_seconds: 0,
+            paused_seconds: 0,
+            interruptions: 0,
+        }
+    }
+    
+    /// Summaries are bucketed by the day rules in force when they were computed,
+    /// so a change of rules throws them all away
+    fn check_summary_rules(&self) -> Result<()> {
+        let rules = format!("{}/{}", self.day_boundary.column(), self.day_start_hour);
+        if self.get_meta("summary_rules")?.as_deref() != Some(rules.as_str()) {
+            self.clear_summaries()?;
+            self.set_meta("summary_rules", &rules)?;
+        }
+        Ok(())
+    }
+    
+    fn invalidate_summary(&self, day: NaiveDate) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM daily_summaries WHERE day = ?1",
+            [day.format("%Y-%m-%d").to_string()],
+        )?;
+        Ok(())
+    }
+    
+    /// Drop the summary of the day a stored session belongs to, before it's changed
+    fn invalidate_session_summary(&self, id: i64) -> Result<()> {
+        let session = self
+            .conn
+            .query_row(
+                "SELECT id, started_at, completed_at, duration_seconds, note, counts_toward_goal, label, end_reason, paused_seconds, interruptions, pause_count, tag
+                 FROM work_sessions WHERE id = ?1",
+                [id],
+                Self::session_from_row,
+            )
+            .optional()?;
+        match session {
+            Some(session) => self.invalidate_summary(self.session_day(&session)),
+            None => Ok(()),
+        }
+    }
+    
+    fn clear_summaries(&self) -> Result<()> {
+        self.conn.execute("DELETE FROM daily_summaries", [])?;
+        Ok(())
+    }
+    
+    /// Day of the most recently completed session, None before the first
+    pub fn get_last_session_day(&self) -> Result<Option<NaiveDate>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, started_at, completed_at, duration_seconds, note, counts_toward_goal, label, end_reason, paused_seconds, interruptions, pause_count, tag
+             FROM work_sessions ORDER BY completed_at DESC LIMIT 1",
+        )?;
+        let session = stmt.query_row([], Self::session_from_row).optional()?;
+        Ok(session.map(|session| self.session_day(&session)))
+    }
+    
+    /// The day a session counts toward under the current day boundary rule
+    pub fn session_day(&self, session: &WorkSession) -> NaiveDate {
+        match self.day_boundary {
+            DayBoundary::StartTime => self.day_of(session.started_at),
+            DayBoundary::CompletionTime => self.day_of(session.completed_at),
+        }
+    }
+    
+    /// The day a timestamp belongs to, using the same shifted boundary as current_day
+    fn day_of(&self, timestamp: DateTime<Utc>) -> NaiveDate {
+        let shifted = timestamp.with_timezone(&Local).naive_local()
+            - chrono::Duration::hours(self.day_start_hour as i64);
+        shifted.date()
+    }
+    
+    /// UTC range [start, end) covering a day, shifted by day_start_hour in local time.
+    /// Stored timestamps are UTC, so bounds must be UTC too for the string comparison to hold.
+    fn day_bounds(&self, day: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        let next_day = day.succ_opt().expect("Date out of range");
+        (self.day_start(day), self.day_start(next_day))
+    }
+    
+    fn day_start(&self, day: NaiveDate) -> DateTime<Utc> {
+        let start = day
+            .and_hms_opt(self.day_start_hour, 0, 0)
+            .expect("Failed to calculate start of day");
+        // earliest() picks the first instant when DST makes the hour ambiguous; if a DST jump
+        // skips it entirely, the day starts an hour later
+        Local
+            .from_local_datetime(&start)
+            .earliest()
+            .or_else(|| Local.from_local_datetime(&(start + chrono::Duration::hours(1))).earliest())
+            .expect("Failed to calculate start of day")
+            .with_timezone(&Utc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .earliest()
+            .expect("Test time doesn't exist locally")
+            .with_timezone(&Utc)
+    }
+
+    fn db_at(now: DateTime<Utc>) -> Database {
+        Database::open_in_memory(Arc::new(MockClock::at(now))).unwrap()
+    }
+
+    fn add_session(db: &Database, started_at: DateTime<Utc>, completed_at: DateTime<Utc>) {
+        db.insert_work_session(&WorkSession::new(started_at, completed_at)).unwrap();
+    }
+
+    #[test]
+    fn database_lives_in_the_app_data_folder() {
+        let path = Database::get_db_path().unwrap();
+        assert!(path.ends_with("pocket_flow/sessions.db"), "{}", path.display());
+    }
+
+    #[test]
+    fn in_memory_database_cannot_be_reopened() {
+        assert!(db_at(local(2024, 3, 2, 10, 0)).reopen().is_err());
+    }
+
+    #[test]
+    fn session_across_midnight_counts_on_the_start_day_by_default() {
+        let mut db = db_at(local(2024, 3, 2, 10, 0));
+        add_session(&db, local(2024, 3, 1, 23, 50), local(2024, 3, 2, 0, 15));
+
+        assert_eq!(db.get_today_totals().unwrap(), (0, 0));
+        let session = db.get_recent_sessions(1, 0).unwrap().remove(0);
+        assert_eq!(db.session_day(&session), NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+
+        db.set_day_boundary(DayBoundary::CompletionTime);
+        assert_eq!(db.get_today_totals().unwrap(), (1, 25 * 60));
+        assert_eq!(db.session_day(&session), NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+    }
+
+    #[test]
+    fn session_across_midnight_counts_on_the_completion_day_when_asked() {
+        let mut db = db_at(local(2024, 3, 1, 23, 55));
+        db.set_day_boundary(DayBoundary::CompletionTime);
+        add_session(&db, local(2024, 3, 1, 23, 50), local(2024, 3, 2, 0, 15));
+
+        assert_eq!(db.get_today_totals().unwrap(), (0, 0));
+        db.set_day_boundary(DayBoundary::StartTime);
+        assert_eq!(db.get_today_totals().unwrap(), (1, 25 * 60));
+    }
+
+    #[test]
+    fn sessions_per_day_fills_in_days_without_sessions() {
+        let db = db_at(local(2024, 3, 7, 18, 0));
+        add_session(&db, local(2024, 3, 1, 9, 0), local(2024, 3, 1, 9, 25));
+        add_session(&db, local(2024, 3, 5, 9, 0), local(2024, 3, 5, 9, 25));
+        add_session(&db, local(2024, 3, 5, 10, 0), local(2024, 3, 5, 10, 50));
+        add_session(&db, local(2024, 3, 7, 9, 0), local(2024, 3, 7, 9, 25));
+
+        let march = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        assert_eq!(
+            db.get_sessions_per_day(3).unwrap(),
+            [(march(5), 2, 75 * 60), (march(6), 0, 0), (march(7), 1, 25 * 60)]
+        );
+        assert_eq!(db.get_sessions_per_day(7).unwrap().len(), 7);
+        assert_eq!(db.get_sessions_per_day(7).unwrap()[0], (march(1), 1, 25 * 60));
+        assert!(db.get_sessions_per_day(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn early_morning_session_counts_on_the_previous_day_before_day_start_hour() {
+        let mut db = db_at(local(2024, 3, 2, 3, 0));
+        db.set_day_start_hour(4);
+        add_session(&db, local(2024, 3, 2, 2, 0), local(2024, 3, 2, 2, 25));
+
+        let previous_day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(db.current_day(), previous_day);
+        assert_eq!(db.day_of(local(2024, 3, 2, 2, 0)), previous_day);
+        assert_eq!(db.day_bounds(previous_day), (local(2024, 3, 1, 4, 0), local(2024, 3, 2, 4, 0)));
+        assert_eq!(db.get_today_totals().unwrap(), (1, 25 * 60));
+        assert_eq!(db.get_sessions_for_day(previous_day).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn pruning_removes_old_work_and_break_sessions() {
+        let db = db_at(local(2024, 3, 10, 12, 0));
+        add_session(&db, local(2024, 3, 1, 9, 0), local(2024, 3, 1, 9, 25));
+        add_session(&db, local(2024, 3, 9, 9, 0), local(2024, 3, 9, 9, 25));
+        for day in [1, 9] {
+            db.save_break_session(&BreakSession {
+                started_at: local(2024, 3, day, 9, 25),
+                ended_at: local(2024, 3, day, 9, 30),
+                duration_seconds: 300,
+                long_break: false,
+                skipped: false,
+                skip_reason: None,
+            })
+            .unwrap();
+        }
+
+        assert_eq!(db.prune_before(NaiveDate::from_ymd_opt(2024, 3, 5).unwrap()).unwrap(), 1);
+        assert_eq!(db.get_recent_sessions(10, 0).unwrap().len(), 1);
+        let breaks = db.get_recent_break_sessions(10).unwrap();
+        assert_eq!(breaks.len(), 1);
+        assert_eq!(breaks[0].ended_at, local(2024, 3, 9, 9, 30));
+    }
+
+    /// A scratch file set up by `sql`, as an older app would have left it
+    fn old_file(name: &str, sql: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pocket_flow_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sessions.db");
+        Connection::open(&path).unwrap().execute_batch(sql).unwrap();
+        path
+    }
+
+    fn open_old_file(path: &Path) -> Database {
+        let clock = Arc::new(MockClock::at(local(2024, 3, 2, 10, 0)));
+        Database::open_at(path, clock).unwrap()
+    }
+
+    #[test]
+    fn old_file_is_upgraded_with_its_rows_intact() {
+        // The first release's file: just the original table, no meta
+        let path = old_file(
+            "baseline_schema",
+            "CREATE TABLE IF NOT EXISTS work_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at TEXT NOT NULL,
+                completed_at TEXT NOT NULL,
+                duration_seconds INTEGER NOT NULL
+            );
+            INSERT INTO work_sessions (started_at, completed_at, duration_seconds)
+                VALUES ('2024-03-01T09:00:00+00:00', '2024-03-01T09:25:00+00:00', 1500);
+            INSERT INTO work_sessions (started_at, completed_at, duration_seconds)
+                VALUES ('2024-03-01T10:00:00+00:00', '2024-03-01T10:20:00+00:00', 1200);",
+        );
+
+        let db = open_old_file(&path);
+
+        assert_eq!(db.get_meta("schema_version").unwrap(), Some(SCHEMA_VERSION.to_string()));
+        assert_eq!(SCHEMA_VERSION, 14);
+        let sessions = db.get_recent_sessions(10, 0).unwrap();
+        assert_eq!(sessions.iter().map(|session| session.duration_seconds).collect::<Vec<_>>(), [1200, 1500]);
+        assert!(sessions.iter().all(|session| session.note.is_none()
+            && session.label.is_none()
+            && session.end_reason == EndReason::Completed
+            && session.counts_toward_goal
+            && session.paused_seconds == 0
+            && session.interruptions == 0
+            && session.pause_count == 0
+            && session.tag.is_none()));
+        assert!(db.get_recent_break_sessions(10).unwrap().is_empty());
+
+        // Every later column is there to write to
+        let mut session = WorkSession::new(local(2024, 3, 2, 9, 0), local(2024, 3, 2, 9, 25));
+        session.label = Some("Writing".to_string());
+        session.pause_count = 1;
+        session.tag = Some("Pocket Flow".to_string());
+        db.insert_work_session(&session).unwrap();
+        db.set_setting("theme", "Midnight").unwrap();
+        assert_eq!(db.get_today_totals().unwrap(), (1, 1500));
+        assert_eq!(
+            db.get_focus_seconds_by_tag().unwrap(),
+            [(None, 2700), (Some("Pocket Flow".to_string()), 1500)]
+        );
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn skipped_breaks_move_into_break_sessions() {
+        // Stopped at version 7, when skips had a table of their own
+        let path = old_file(
+            "skipped_breaks_schema",
+            "CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            INSERT INTO meta VALUES ('schema_version', '7');
+            CREATE TABLE work_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at TEXT NOT NULL,
+                completed_at TEXT NOT NULL,
+                duration_seconds INTEGER NOT NULL,
+                note TEXT,
+                counts_toward_goal INTEGER NOT NULL DEFAULT 1,
+                label TEXT,
+                end_reason TEXT NOT NULL DEFAULT 'completed'
+            );
+            CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            CREATE TABLE skipped_breaks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skipped_at TEXT NOT NULL,
+                long_break INTEGER NOT NULL,
+                reason TEXT
+            );
+            INSERT INTO work_sessions (started_at, completed_at, duration_seconds, note, label)
+                VALUES ('2024-03-01T09:00:00+00:00', '2024-03-01T09:25:00+00:00', 1500, 'Draft', 'Writing');
+            INSERT INTO skipped_breaks (skipped_at, long_break, reason) VALUES ('2024-03-01T09:26:00+00:00', 0, 'Call');",
+        );
+
+        let db = open_old_file(&path);
+
+        assert_eq!(db.get_meta("schema_version").unwrap(), Some(SCHEMA_VERSION.to_string()));
+        let sessions = db.get_recent_sessions(10, 0).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].note.as_deref(), Some("Draft"));
+        assert_eq!(sessions[0].label.as_deref(), Some("Writing"));
+        let breaks = db.get_recent_break_sessions(10).unwrap();
+        assert_eq!(breaks.len(), 1);
+        assert!(breaks[0].skipped);
+        assert_eq!(breaks[0].skip_reason.as_deref(), Some("Call"));
+        assert_eq!(breaks[0].duration_seconds, 0);
+        let skipped_table: bool = db
+            .conn
+            .query_row("SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'skipped_breaks'", [], |row| row.get(0))
+            .unwrap();
+        assert!(!skipped_table);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    fn backup_file(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pocket_flow_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("backup.csv");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn malformed_backup_row_fails_the_import() {
+        let db = db_at(local(2024, 3, 2, 10, 0));
+        let path = backup_file(
+            "malformed_backup",
+            "started_at,completed_at,duration_seconds
+2024-03-01T09:00:00+00:00,2024-03-01T09:25:00+00:00,1500
+2024-03-01T10:00:00+00:00,yesterday,1500
+2024-03-01T11:00:00+00:00,2024-03-01T11:25:00+00:00,soon
+",
+        );
+
+        let error = db.import_sessions_csv(&path).unwrap_err().to_string();
+
+        assert!(error.starts_with("Line 3:"), "{}", error);
+        assert!(error.contains("completed_at"), "{}", error);
+        assert_eq!(db.get_total_session_count().unwrap(), 0);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn backup_import_skips_sessions_already_stored() {
+        let db = db_at(local(2024, 3, 2, 10, 0));
+        add_session(&db, local(2024, 3, 1, 9, 0), local(2024, 3, 1, 9, 25));
+        let started_at = local(2024, 3, 1, 9, 0).to_rfc3339();
+        let path = backup_file(
+            "duplicate_backup",
+            &format!(
+                "started_at,completed_at,duration_seconds,note,label
+{},{},1500,,
+{},{},1200,Draft,Writing
+",
+                started_at,
+                local(2024, 3, 1, 9, 25).to_rfc3339(),
+                local(2024, 3, 1, 10, 0).to_rfc3339(),
+                local(2024, 3, 1, 10, 25).to_rfc3339(),
+            ),
+        );
+
+        assert_eq!(db.import_sessions_csv(&path).unwrap(), 1);
+
+        let sessions = db.get_recent_sessions(10, 0).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].duration_seconds, 1200);
+        assert_eq!(sessions[0].note.as_deref(), Some("Draft"));
+        assert_eq!(sessions[0].label.as_deref(), Some("Writing"));
+        assert_eq!(db.import_sessions_csv(&path).unwrap(), 0);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
 }
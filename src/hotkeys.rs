@@ -0,0 +1,157 @@
+use std::sync::mpsc::{self, Receiver};
+
+use eframe::egui;
+use global_hotkey::hotkey::HotKey;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+use crate::PomodoroApp;
+
+pub const DEFAULT_START_PAUSE: &str = "CmdOrCtrl+Shift+P";
+pub const DEFAULT_SKIP_BREAK: &str = "CmdOrCtrl+Shift+K";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HotkeyAction {
+    StartPause,
+    SkipBreak,
+}
+
+impl HotkeyAction {
+    fn label(&self) -> &'static str {
+        match self {
+            HotkeyAction::StartPause => "Start/pause",
+            HotkeyAction::SkipBreak => "Skip break",
+        }
+    }
+}
+
+/// Shortcuts registered with the OS, so they work while another app has focus or the window
+/// is minimized. Failing to register one only leaves that action without a shortcut.
+pub struct GlobalHotkeys {
+    manager: Result<GlobalHotKeyManager, String>,
+    events: Receiver<GlobalHotKeyEvent>,
+    registered: Vec<(HotKey, HotkeyAction)>,
+    errors: Vec<String>, // From the last register, shown in settings
+}
+
+impl GlobalHotkeys {
+    /// Presses also repaint ctx, since nothing else wakes a stopped, minimized window
+    pub fn new(ctx: &egui::Context) -> Self {
+        let (sender, events) = mpsc::channel();
+        let ctx = ctx.clone();
+        GlobalHotKeyEvent::set_event_handler(Some(move |event| {
+            // The receiving end goes away with the app
+            let _ = sender.send(event);
+            ctx.request_repaint();
+        }));
+
+        Self {
+            manager: GlobalHotKeyManager::new().map_err(|e| format!("Global shortcuts aren't available: {}", e)),
+            events,
+            registered: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Replace the registered shortcuts with these, in the "CmdOrCtrl+Shift+P" form. An empty
+    /// combo leaves its action without a shortcut.
+    pub fn register(&mut self, start_pause: &str, skip_break: &str) {
+        self.errors.clear();
+        let manager = match &self.manager {
+            Ok(manager) => manager,
+            Err(e) => {
+                self.errors.push(e.clone());
+                return;
+            }
+        };
+        for (hotkey, _) in self.registered.drain(..) {
+            if let Err(e) = manager.unregister(hotkey) {
+                eprintln!("Failed to unregister shortcut: {}", e);
+            }
+        }
+
+        for (combo, action) in [(start_pause, HotkeyAction::StartPause), (skip_break, HotkeyAction::SkipBreak)] {
+            let combo = combo.trim();
+            if combo.is_empty() {
+                continue;
+            }
+            let result = combo
+                .parse::<HotKey>()
+                .map_err(|e| e.to_string())
+                .and_then(|hotkey| manager.register(hotkey).map(|()| hotkey).map_err(|e| e.to_string()));
+            match result {
+                Ok(hotkey) => self.registered.push((hotkey, action)),
+                Err(e) => {
+                    eprintln!("Failed to register {} shortcut {}: {}", action.label(), combo, e);
+                    self.errors.push(format!("{} ({}): {}", action.label(), combo, e));
+                }
+            }
+        }
+    }
+
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// Actions whose shortcut was pressed since the last call
+    fn pressed(&self) -> Vec<HotkeyAction> {
+        self.events
+            .try_iter()
+            .filter(|event| event.state == HotKeyState::Pressed)
+            .filter_map(|event| {
+                self.registered
+                    .iter()
+                    .find(|(hotkey, _)| hotkey.id() == event.id)
+                    .map(|(_, action)| *action)
+            })
+            .collect()
+    }
+}
+
+impl PomodoroApp {
+    pub(crate) fn handle_hotkey_events(&mut self, ctx: &egui::Context) {
+        let Some(hotkeys) = &self.hotkeys else { return };
+        for action in hotkeys.pressed() {
+            match action {
+                HotkeyAction::StartPause => self.toggle_start_pause(ctx),
+                HotkeyAction::SkipBreak => {
+                    let break_running = self.mode.is_break() && self.remaining_seconds > 0;
+                    if break_running || self.pending_break.is_some() {
+                        self.request_skip(ctx);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn apply_hotkeys(&mut self) {
+        if let Some(hotkeys) = &mut self.hotkeys {
+            hotkeys.register(&self.settings.hotkey_start_pause, &self.settings.hotkey_skip_break);
+        }
+    }
+
+    /// Settings rows for the two shortcuts, applied once editing finishes
+    pub(crate) fn hotkeys_section(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        egui::Grid::new("global_hotkeys").num_columns(2).show(ui, |ui| {
+            for (action, combo) in [
+                (HotkeyAction::StartPause, &mut self.settings.hotkey_start_pause),
+                (HotkeyAction::SkipBreak, &mut self.settings.hotkey_skip_break),
+            ] {
+                ui.label(action.label());
+                let response = ui.add(egui::TextEdit::singleline(combo).hint_text("None").desired_width(160.0));
+                changed |= response.lost_focus();
+                ui.end_row();
+            }
+        })
+        .response
+        .on_hover_text("Work from any app, e.g. CmdOrCtrl+Shift+P or Alt+F9; leave empty for none");
+
+        if changed {
+            self.apply_hotkeys();
+        }
+        for error in self.hotkeys.iter().flat_map(GlobalHotkeys::errors) {
+            ui.label(egui::RichText::new(error).small().color(ui.visuals().warn_fg_color));
+        }
+        changed
+    }
+}
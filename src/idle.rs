@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use crate::{PomodoroApp, PomodoroMode, TimerState};
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(5); // How often the system idle time is polled
+
+impl PomodoroApp {
+    /// Pause a running work session once there's been no keyboard or mouse input anywhere for
+    /// Settings::idle_threshold_secs. The time spent idle is taken back out of the session, and
+    /// it stays paused until Resume is pressed.
+    pub(crate) fn check_idle(&mut self) {
+        let threshold = self.settings.idle_threshold_secs;
+        if threshold == 0 || self.mode != PomodoroMode::Work || !self.state.is_running() {
+            return;
+        }
+        let now = self.clock.now_instant();
+        if self.last_idle_check.is_some_and(|last| now.duration_since(last) < IDLE_CHECK_INTERVAL) {
+            return;
+        }
+        self.last_idle_check = Some(now);
+
+        let Some(idle) = seconds_since_input() else { return };
+        if idle < threshold {
+            return;
+        }
+
+        // The timer kept running while nobody was there, so give that time back
+        let remaining = self.state.remaining_at(now).unwrap_or(self.remaining_seconds);
+        self.remaining_seconds = remaining.saturating_add(idle).min(self.work_duration);
+        if let Some(period) = &mut self.work_period {
+            period.pause(now.checked_sub(Duration::from_secs(idle)).unwrap_or(now));
+        }
+        self.state = TimerState::Paused;
+        self.idle_paused = true;
+        self.update_menu_bar();
+    }
+}
+
+/// Seconds since the last keyboard or mouse input in any app. None where the platform
+/// doesn't say, in which case idle detection is off.
+#[cfg(target_os = "macos")]
+pub fn seconds_since_input() -> Option<u64> {
+    const COMBINED_SESSION_STATE: i32 = 0; // kCGEventSourceStateCombinedSessionState
+    const ANY_INPUT_EVENT: u32 = !0; // kCGAnyInputEventType
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+    }
+
+    let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT) };
+    (seconds.is_finite() && seconds >= 0.0).then_some(seconds as u64)
+}
+
+#[cfg(target_os = "windows")]
+pub fn seconds_since_input() -> Option<u64> {
+    #[repr(C)]
+    struct LastInputInfo {
+        cb_size: u32,
+        dw_time: u32, // Tick count of the last input
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetLastInputInfo(plii: *mut LastInputInfo) -> i32;
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+    }
+
+    let mut info = LastInputInfo { cb_size: std::mem::size_of::<LastInputInfo>() as u32, dw_time: 0 };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    // Tick counts wrap every 49.7 days
+    let idle_ms = unsafe { GetTickCount() }.wrapping_sub(info.dw_time);
+    Some(u64::from(idle_ms) / 1000)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn seconds_since_input() -> Option<u64> {
+    None
+}
+
+/// Whether seconds_since_input works here, so settings can say when it doesn't
+pub fn idle_detection_supported() -> bool {
+    cfg!(any(target_os = "macos", target_os = "windows"))
+}
@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::path::Path;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+
+use crate::db::Database;
+use crate::models::WorkSession;
+
+/// CSV layouts the importer understands
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ImportFormat {
+    Native, // Our own export: started_at, completed_at, ... as RFC3339
+    Toggl,  // Toggl detailed report: Start date, Start time, End date, End time, ...
+    Forest, // Forest export: Start Time, End Time, Tag, Note, Tree Type, Is Success
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 3] = [ImportFormat::Native, ImportFormat::Toggl, ImportFormat::Forest];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportFormat::Native => "Pocket Flow",
+            ImportFormat::Toggl => "Toggl",
+            ImportFormat::Forest => "Forest",
+        }
+    }
+
+    /// Recognize a format from its header row
+    pub fn detect(headers: &csv::StringRecord) -> Option<ImportFormat> {
+        let has = |name: &str| headers.iter().any(|h| h.trim().eq_ignore_ascii_case(name));
+        if has("started_at") && has("completed_at") {
+            Some(ImportFormat::Native)
+        } else if has("Start date") && has("Start time") && has("End date") && has("End time") {
+            Some(ImportFormat::Toggl)
+        } else if has("Start Time") && has("End Time") {
+            Some(ImportFormat::Forest)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportReport {
+    pub format: ImportFormat,
+    pub imported: usize,
+    pub skipped: usize, // Zero-length, unsuccessful or duplicate rows
+}
+
+impl ImportReport {
+    /// "Imported 12 sessions (Toggl), skipped 3 rows"
+    pub fn summary(&self) -> String {
+        format!(
+            "Imported {} sessions ({}), skipped {} rows",
+            self.imported,
+            self.format.label(),
+            self.skipped
+        )
+    }
+}
+
+/// Import sessions from a CSV file, auto-detecting the format when none is given.
+/// Nothing is imported if a row is malformed; the error names the first bad line.
+pub fn import_csv(
+    db: &Database,
+    path: &Path,
+    format: Option<ImportFormat>,
+) -> Result<ImportReport, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_path(path)?;
+    let headers = reader.headers()?.clone();
+    let format = match format.or_else(|| ImportFormat::detect(&headers)) {
+        Some(format) => format,
+        None => return Err("Unrecognized CSV format".into()),
+    };
+
+    // Our own backups keep paused time out of their durations, and the database reads them as saved
+    if format == ImportFormat::Native {
+        let rows = reader.records().count();
+        let imported = db.import_sessions_csv(path)?;
+        return Ok(ImportReport { format, imported, skipped: rows - imported });
+    }
+
+    let column = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let mut sessions = Vec::new();
+    let mut rows: usize = 0;
+
+    for record in reader.records() {
+        rows += 1;
+        let record = record?;
+        let line = record.position().map_or(rows as u64 + 1, |p| p.line());
+        let field = |name: &str| column(name).and_then(|i| record.get(i)).map(str::trim);
+
+        let parsed = match format {
+            ImportFormat::Native => unreachable!("backups are read by Database::import_sessions_csv"),
+            ImportFormat::Toggl => {
+                let start = field("Start date").zip(field("Start time"))
+                    .and_then(|(date, time)| parse_local(&format!("{} {}", date, time)));
+                let end = field("End date").zip(field("End time"))
+                    .and_then(|(date, time)| parse_local(&format!("{} {}", date, time)));
+                start.zip(end).map(|times| (times, field("Description")))
+            }
+            ImportFormat::Forest => {
+                // Withered trees are sessions the user gave up on
+                if field("Is Success").is_some_and(|s| s.eq_ignore_ascii_case("false")) {
+                    continue;
+                }
+                field("Start Time")
+                    .and_then(parse_any)
+                    .zip(field("End Time").and_then(parse_any))
+                    .map(|times| (times, field("Note")))
+            }
+        };
+
+        let Some(((started_at, completed_at), note)) = parsed else {
+            return Err(format!("Line {}: couldn't read the start and end times", line).into());
+        };
+        if completed_at <= started_at {
+            continue;
+        }
+
+        let mut session = WorkSession::new(started_at, completed_at);
+        session.note = note.filter(|n| !n.is_empty()).map(str::to_string);
+        sessions.push(session);
+    }
+
+    let imported = db.import_sessions(&sessions)?;
+    Ok(ImportReport {
+        format,
+        imported,
+        skipped: rows - imported,
+    })
+}
+
+fn parse_rfc3339(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Timestamps without an offset are taken as local time
+fn parse_local(value: &str) -> Option<DateTime<Utc>> {
+    const FORMATS: [&str; 4] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%m/%d/%Y %H:%M:%S", "%m/%d/%Y %H:%M"];
+    FORMATS.iter().find_map(|format| {
+        let naive = NaiveDateTime::parse_from_str(value, format).ok()?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+    })
+}
+
+/// Forest has used several timestamp styles across versions
+fn parse_any(value: &str) -> Option<DateTime<Utc>> {
+    const OFFSET_FORMATS: [&str; 3] = ["%Y-%m-%d %H:%M:%S %z", "%a %b %d %H:%M:%S GMT%z %Y", "%a %b %d %H:%M:%S %z %Y"];
+    parse_rfc3339(value)
+        .or_else(|| {
+            OFFSET_FORMATS.iter().find_map(|format| {
+                DateTime::parse_from_str(value, format)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc))
+            })
+        })
+        .or_else(|| parse_local(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn backup_round_trip_keeps_notes_labels_and_durations() {
+        let dir = std::env::temp_dir().join(format!("pocket_flow_backup_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("backup.csv");
+        let started_at = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::at(started_at));
+
+        let original = Database::open_in_memory(clock.clone()).unwrap();
+        let mut session = WorkSession::new(started_at, started_at + chrono::Duration::minutes(30));
+        session.duration_seconds = 25 * 60;
+        session.note = Some("Outline, part 2".to_string());
+        session.label = Some("Writing".to_string());
+        original.insert_work_session(&session).unwrap();
+        original
+            .insert_work_session(&WorkSession::new(
+                started_at + chrono::Duration::hours(1),
+                started_at + chrono::Duration::minutes(85),
+            ))
+            .unwrap();
+        assert_eq!(original.export_sessions_csv(&path).unwrap(), 2);
+
+        let restored = Database::open_in_memory(clock).unwrap();
+        let report = import_csv(&restored, &path, None).unwrap();
+        assert_eq!((report.format, report.imported, report.skipped), (ImportFormat::Native, 2, 0));
+
+        let sessions = restored.get_recent_sessions(10, 0).unwrap();
+        assert_eq!(sessions[1].duration_seconds, 25 * 60);
+        assert_eq!(sessions[1].note.as_deref(), Some("Outline, part 2"));
+        assert_eq!(sessions[1].label.as_deref(), Some("Writing"));
+        assert_eq!((sessions[0].note.as_deref(), sessions[0].label.as_deref()), (None, None));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,52 @@
+use eframe::egui;
+
+use crate::controls::{control_buttons, ControlAction};
+use crate::theme::Theme;
+use crate::{PomodoroApp, PomodoroMode};
+
+impl PomodoroApp {
+    /// Pause the work session and count an interruption against it. The timer resumes from
+    /// the prompt shown in place of the controls.
+    pub(crate) fn interrupt(&mut self) {
+        if self.mode != PomodoroMode::Work || !self.state.is_running() {
+            return;
+        }
+        if let Some(period) = &mut self.work_period {
+            period.record_interruption();
+        }
+        self.pause();
+        self.interruption_note = Some(String::new());
+    }
+
+    pub(crate) fn session_interruptions(&self) -> u32 {
+        self.work_period.as_ref().map_or(0, |period| period.interruptions())
+    }
+
+    /// Optional note on what interrupted, then back to work
+    pub(crate) fn interruption_prompt(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, theme: &Theme) {
+        let Some(note) = &mut self.interruption_note else { return };
+        ui.label(
+            egui::RichText::new("Interrupted — what came up?")
+                .size(16.0)
+                .color(theme.background)
+        );
+        ui.add_space(8.0);
+        let response = ui.add(
+            egui::TextEdit::singleline(note)
+                .hint_text("Optional note")
+                .desired_width(240.0),
+        );
+        let submitted = response.lost_focus() && ctx.input(|i| i.key_pressed(egui::Key::Enter));
+        ui.add_space(8.0);
+
+        if control_buttons(ui, 100.0, &[ControlAction::Resume]).is_some() || submitted {
+            let note = self.interruption_note.take().unwrap_or_default();
+            if let Some(period) = &mut self.work_period {
+                if !note.trim().is_empty() {
+                    period.note_interruption(note.trim());
+                }
+            }
+            self.start(ctx);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+/// How numbers and durations are written. `Default` keeps the app's original
+/// formatting; the others follow common conventions for that language.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberLocale {
+    #[default]
+    Default, // 1234, 1.5, 1h15m
+    English, // 1,234, 1.5, 1h 15m
+    German,  // 1.234, 1,5, 1 Std. 15 Min.
+    French,  // 1 234, 1,5, 1 h 15 min
+}
+
+impl NumberLocale {
+    pub const ALL: [NumberLocale; 4] = [
+        NumberLocale::Default,
+        NumberLocale::English,
+        NumberLocale::German,
+        NumberLocale::French,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NumberLocale::Default => "Default",
+            NumberLocale::English => "English",
+            NumberLocale::German => "Deutsch",
+            NumberLocale::French => "Français",
+        }
+    }
+
+    fn group_separator(&self) -> Option<char> {
+        match self {
+            NumberLocale::Default => None,
+            NumberLocale::English => Some(','),
+            NumberLocale::German => Some('.'),
+            NumberLocale::French => Some('\u{202F}'), // Narrow no-break space
+        }
+    }
+
+    fn decimal_separator(&self) -> char {
+        match self {
+            NumberLocale::Default | NumberLocale::English => '.',
+            NumberLocale::German | NumberLocale::French => ',',
+        }
+    }
+
+    /// Whole number with digit grouping, e.g. 12,345
+    pub fn integer(&self, value: i64) -> String {
+        let digits = value.unsigned_abs().to_string();
+        let grouped = match self.group_separator() {
+            Some(separator) => {
+                let mut grouped = String::new();
+                for (i, digit) in digits.chars().enumerate() {
+                    if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                        grouped.push(separator);
+                    }
+                    grouped.push(digit);
+                }
+                grouped
+            }
+            None => digits,
+        };
+        if value < 0 {
+            format!("-{}", grouped)
+        } else {
+            grouped
+        }
+    }
+
+    /// Number with one decimal place, e.g. 1,234.5
+    pub fn decimal(&self, value: f64) -> String {
+        let tenths = (value * 10.0).round() as i64;
+        let whole = self.integer(tenths / 10);
+        let sign = if tenths < 0 && tenths / 10 == 0 { "-" } else { "" };
+        format!("{}{}{}{}", sign, whole, self.decimal_separator(), (tenths % 10).abs())
+    }
+
+    /// Focus time in days, hours and minutes, e.g. "1h15m", "45m" or "3d04h00m" by default.
+    /// Days start at 24 hours so long totals stay readable; negative input counts as zero.
+    pub fn duration(&self, seconds: i64) -> String {
+        let minutes = seconds.max(0) / 60;
+        let (hours, minutes) = (minutes / 60, minutes % 60);
+        let (days, hours) = (hours / 24, hours % 24);
+
+        let (units, gap, separator, padded) = self.duration_units();
+        let values = [days, hours, minutes];
+        // Everything from the largest nonzero unit down is shown
+        let first = values.iter().position(|value| *value > 0).unwrap_or(values.len() - 1);
+        (first..values.len())
+            .map(|i| {
+                let value = match i == first {
+                    true => self.integer(values[i]),
+                    false if padded => format!("{:02}", values[i]),
+                    false => values[i].to_string(),
+                };
+                format!("{}{}{}", value, gap, units[i])
+            })
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// Long totals in hours and minutes without rolling over into days, e.g. "1,234h 05m"
+    pub fn hours_minutes(&self, seconds: i64) -> String {
+        let minutes = seconds.max(0) / 60;
+        let (units, gap, _, _) = self.duration_units();
+        format!("{}{}{} {:02}{}{}", self.integer(minutes / 60), gap, units[1], minutes % 60, gap, units[2])
+    }
+
+    /// Unit names, gap before a unit, gap between parts, zero-padded trailing parts
+    fn duration_units(&self) -> ([&'static str; 3], &'static str, &'static str, bool) {
+        match self {
+            NumberLocale::Default => (["d", "h", "m"], "", "", true),
+            NumberLocale::English => (["d", "h", "m"], "", " ", false),
+            NumberLocale::German => (["Tg.", "Std.", "Min."], " ", " ", false),
+            NumberLocale::French => (["j", "h", "min"], " ", " ", false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_integers_are_grouped_per_locale() {
+        assert_eq!(NumberLocale::Default.integer(1_234_567), "1234567");
+        assert_eq!(NumberLocale::English.integer(1_234_567), "1,234,567");
+        assert_eq!(NumberLocale::German.integer(1_234_567), "1.234.567");
+        assert_eq!(NumberLocale::French.integer(1_234_567), "1\u{202F}234\u{202F}567");
+        assert_eq!(NumberLocale::English.integer(-1_234), "-1,234");
+        assert_eq!(NumberLocale::English.integer(i64::MIN), "-9,223,372,036,854,775,808");
+        assert_eq!(NumberLocale::English.integer(999), "999");
+    }
+
+    #[test]
+    fn large_decimals_and_durations_keep_the_grouping() {
+        assert_eq!(NumberLocale::German.decimal(1_234.5), "1.234,5");
+        assert_eq!(NumberLocale::English.decimal(-0.5), "-0.5");
+
+        let long_total = 1_234 * 3600 + 5 * 60;
+        assert_eq!(NumberLocale::Default.hours_minutes(long_total), "1234h 05m");
+        assert_eq!(NumberLocale::English.hours_minutes(long_total), "1,234h 05m");
+        assert_eq!(NumberLocale::German.hours_minutes(long_total), "1.234 Std. 05 Min.");
+
+        assert_eq!(NumberLocale::Default.duration(3 * 86_400 + 4 * 3600), "3d04h00m");
+        assert_eq!(NumberLocale::English.duration(1_234 * 86_400), "1,234d 0h 0m");
+        assert_eq!(NumberLocale::French.duration(-60), "0 min");
+    }
+}
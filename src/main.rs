@@ -1,34 +1,97 @@
 use eframe::egui;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use chrono::{DateTime, Utc};
-use tray_icon::{TrayIcon, TrayIconBuilder};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use tray_icon::TrayIcon;
 
+mod audio;
+mod autocomplete;
+mod clock;
+mod config;
+mod contract;
+mod controls;
 mod db;
+mod hotkeys;
+mod idle;
+mod import;
+mod interruptions;
+mod locale;
+mod maintenance;
 mod models;
+mod notifications;
+mod profiles;
+mod progress_ring;
+mod quit_confirm;
+mod session_goal;
+mod session_writer;
+mod settings_window;
+mod skip_reason;
+#[cfg(unix)]
+mod state_socket;
+mod stats;
+mod stats_card;
+mod stats_window;
+mod suggestions;
+mod theme;
+mod tray;
+mod webhook;
+mod window_geometry;
+mod work_period;
 
+use audio::{AmbientPlayer, Chime};
+use autocomplete::autocomplete_edit;
+use clock::{Clock, SystemClock};
+use config::{BreakEndAction, DisplayChangeAction, EscapeAction, Profile, Settings, SettingsBackend};
+use contract::DEFAULT_CONTRACT_SESSIONS;
+use controls::{control_buttons, ControlAction};
 use db::Database;
-use models::WorkSession;
+use hotkeys::GlobalHotkeys;
+use import::ImportFormat;
+use models::{BreakSession, EndReason, FocusContract, WorkSession};
+use session_goal::progress_dots;
+use session_writer::SessionWriter;
+#[cfg(unix)]
+use state_socket::StateSocket;
+use stats::{DayTotal, Summary};
+use suggestions::BreakSuggestions;
+use theme::{apply_theme, Theme};
+use tray::TrayMenu;
+use webhook::Webhook;
+use window_geometry::WindowGeometry;
+use work_period::WorkPeriod;
 
-const WORK_DURATION: u64 = 25 * 60; // 25 minutes in seconds
-const BREAK_DURATION: u64= 5 * 60; // 5 minutes in seconds
-
-// Test Values
-// const WORK_DURATION: u64 = 5; 
-// const BREAK_DURATION: u64 = 5;
-
-// Color Palette
-const COLOR_MAIN: egui::Color32 = egui::Color32::from_rgb(0x00, 0x12, 0x40); // #001240
-const COLOR_BACKGROUND: egui::Color32 = egui::Color32::from_rgb(0xFA, 0xFA, 0xFA); // #FAFAFA
-const COLOR_ACCENT: egui::Color32 = egui::Color32::from_rgb(0xFF, 0x73, 0x1C); // #FF731C
-const COLOR_ALT_WHITE: egui::Color32 = egui::Color32::from_rgb(0xFF, 0xF7, 0xEA); // #FFF7EA
-const COLOR_SECONDARY: egui::Color32 = egui::Color32::from_rgb(0x60, 0x9E, 0xF6); // #609EF6
-const COLOR_SECONDARY_DARK: egui::Color32 = egui::Color32::from_rgb(0x16, 0x46, 0xA1); // #1646A1 
+const SESSIONS_PER_CYCLE: usize = 4; // Work sessions before a long break
+const FADE_DURATION: Duration = Duration::from_millis(300); // Work/break screen transition
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(2); // Keeps quitting snappy
+const TIMER_FONT_RANGE: (f32, f32) = (32.0, 220.0); // Work timer size limits, in points
+const FINAL_MINUTE_SCALE: f32 = 1.25; // Seconds-only countdown is this much larger, see Settings::final_minute_seconds
+const WORK_SCREEN_CHROME: f32 = 264.0; // Height taken by everything on the work screen but the timer
+const CLOCK_TOLERANCE: i64 = 60; // Seconds a saved session may fall short of its work period before it's flagged
+const BREAK_DELAY: Duration = Duration::from_secs(60); // Added per delay during the break grace countdown
+const DAY_CHECK_INTERVAL: Duration = Duration::from_secs(60); // How often the app looks for a new day
 
 fn main() -> eframe::Result<()> {
+    let settings_backend = SettingsBackend::from_args();
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+    let db = Database::new(clock.clone()).expect("Failed to initialize database");
+    let settings = Settings::load(settings_backend, &db);
+    
+    // Transparency can only be requested when the window is created
+    let transparent = settings.wants_transparent_window();
+    
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size([400.0, 328.0])
+        .with_min_inner_size(settings.min_window_size())
+        .with_resizable(true)
+        .with_transparent(transparent);
+    match db.get_window_geometry() {
+        Ok(Some(geometry)) => viewport = geometry.apply(viewport, settings.min_window_size()),
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to read window position: {}", e),
+    }
+    
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([400.0, 300.0])
-            .with_resizable(true),
+        viewport,
         ..Default::default()
     };
 
@@ -44,7 +107,7 @@ fn main() -> eframe::Result<()> {
                 "SF Pro Display".to_owned(),
                 egui::FontData::from_static(include_bytes!(
                     "../assets/fonts/sf-pro-display/SFPRODISPLAYREGULAR.OTF"
-                )).into(),
+                )),
             );
             
             // Load SF Pro Display Bold
@@ -52,7 +115,7 @@ fn main() -> eframe::Result<()> {
                 "SF Pro Display Bold".to_owned(),
                 egui::FontData::from_static(include_bytes!(
                     "../assets/fonts/sf-pro-display/SFPRODISPLAYBOLD.OTF"
-                )).into(),
+                )),
             );
             
             // Load SF Pro Display Medium
@@ -60,7 +123,7 @@ fn main() -> eframe::Result<()> {
                 "SF Pro Display Medium".to_owned(),
                 egui::FontData::from_static(include_bytes!(
                     "../assets/fonts/sf-pro-display/SFPRODISPLAYMEDIUM.OTF"
-                )).into(),
+                )),
             );
             
             // Set SF Pro Display as the default proportional font
@@ -77,138 +140,564 @@ fn main() -> eframe::Result<()> {
             
             cc.egui_ctx.set_fonts(fonts);
             
-            Ok(Box::new(PomodoroApp::default()))
+            let mut app = PomodoroApp::new(db, settings, settings_backend, clock);
+            app.connect_desktop(&cc.egui_ctx);
+            Ok(Box::new(app))
         }),
     )
 }
 
+/// Timer state. The deadline lives in Running itself, so the timer can't be running
+/// without something to count down to.
 #[derive(PartialEq, Clone, Copy)]
 enum TimerState {
     Stopped,
-    Running,
-    Paused,
+    Running { deadline: Instant },
+    Paused, // Remaining time is frozen in PomodoroApp::remaining_seconds
+}
+
+impl TimerState {
+    /// Running, ending `seconds` after `now`
+    fn running_for(seconds: u64, now: Instant) -> Self {
+        TimerState::Running {
+            deadline: now + Duration::from_secs(seconds),
+        }
+    }
+    
+    fn is_running(&self) -> bool {
+        matches!(self, TimerState::Running { .. })
+    }
+    
+    /// Whole seconds left at `now`, rounded up so the display only hits 00:00 at the end, and
+    /// zero once the deadline has passed however long ago. None unless running. Derived from
+    /// the deadline alone, so any time between calls (a stall, a throttled window) is counted.
+    fn remaining_at(&self, now: Instant) -> Option<u64> {
+        let TimerState::Running { deadline } = self else { return None };
+        let left = deadline.saturating_duration_since(now);
+        Some(left.as_secs() + u64::from(left.subsec_nanos() > 0))
+    }
+    
+    #[cfg(unix)]
+    fn as_str(&self) -> &'static str {
+        match self {
+            TimerState::Stopped => "stopped",
+            TimerState::Running { .. } => "running",
+            TimerState::Paused => "paused",
+        }
+    }
 }
 
 #[derive(PartialEq, Clone, Copy)]
 enum PomodoroMode {
     Work,
     Break,
+    LongBreak,
+}
+
+impl PomodoroMode {
+    #[cfg(unix)]
+    fn as_str(&self) -> &'static str {
+        match self {
+            PomodoroMode::Work => "work",
+            PomodoroMode::Break => "break",
+            PomodoroMode::LongBreak => "long_break",
+        }
+    }
+    
+    fn is_break(&self) -> bool {
+        matches!(self, PomodoroMode::Break | PomodoroMode::LongBreak)
+    }
+
 }
 
 struct PomodoroApp {
     mode: PomodoroMode,
     state: TimerState,
+    work_duration: u64,  // Seconds, from Settings::work_minutes
+    break_duration: u64, // Seconds, from Settings::break_minutes
+    long_break_duration: u64, // Seconds, from Settings::long_break_minutes
     remaining_seconds: u64,
-    last_tick: Option<Instant>,
-    work_session_start: Option<DateTime<Utc>>,
+    completion_fired: bool, // Set once the current period's completion has been handled
+    wind_down_fired: bool,  // Set once the pre-break warning has been shown this work period
+    work_period: Option<WorkPeriod>, // Start and pause time of the work session in progress
+    current_label: String, // Task label for the running work session, kept for the next one
+    current_tag: Option<String>, // Project tag for the running work session, kept like the label
+    current_note: String, // Typed on the work screen, saved as the session's note
+    known_labels: Vec<String>, // Earlier labels, most recent first, for autocomplete
     today_session_count: usize,
+    today_goal_count: usize, // Of those, the ones that count toward the goal, see Settings::goal_requires_break
+    today_focus_seconds: i64,
+    counted_day: NaiveDate, // Day the today_ totals were counted for, to notice midnight passing
+    last_day_check: Option<Instant>,
+    cycle_position: usize, // Work sessions completed in the current cycle, stored across restarts
+    long_break_on_demand: bool, // The current long break was taken early rather than earned
+    goal_pending_session: Option<i64>, // Saved session waiting for its break to count toward the goal
+    goal_pending_write: bool, // The session for goal_pending_session is still being written
+    focus_contract: Option<FocusContract>, // Latest commitment, possibly from an earlier day
+    contract_input: u32, // Value being entered in the commitment prompt
+    contract_prompt_dismissed: bool, // Closed without answering; asked again next launch
+    welcome_back: bool, // Greeting shown until the first session after a long gap starts
+    skip_reason_prompt: bool, // Asking why the break is being skipped, see skip_reason.rs
+    quit_prompt: bool, // Asking before quitting mid-session, see quit_confirm.rs
+    quit_confirmed: bool, // Quit was confirmed, so the next close request goes through
+    interruption_note: Option<String>, // Set while asking what interrupted, see interruptions.rs
+    idle_paused: bool, // Paused by idle detection, until resumed by hand, see idle.rs
+    last_idle_check: Option<Instant>,
+    break_ready: bool, // Work ended without an automatic break, see Settings::auto_break
+    break_started_at: Option<DateTime<Utc>>, // Set while a break runs, for the saved BreakSession
+    pending_break: Option<(PomodoroMode, Instant)>, // Break waiting out its grace countdown, and when it goes fullscreen
     db: Database,
+    session_writer: SessionWriter, // Saves work sessions off the UI thread
+    clock: Arc<dyn Clock>, // Shared with the database
+    settings: Settings,
+    settings_backend: SettingsBackend,
+    settings_open: bool,
+    transparent_window: bool, // Whether the viewport was created transparent
+    stats_open: bool,
+    history: Vec<WorkSession>, // Most recent first, a page at a time
+    history_complete: bool, // Every session is in history, so there's nothing more to load
+    break_history: Vec<BreakSession>, // Most recent first
+    today_sessions: Vec<WorkSession>, // Oldest first, for the timeline strip
+    daily_totals: Vec<DayTotal>,
+    week_totals: Vec<(NaiveDate, usize, i64)>, // Sessions and focus seconds for the last 7 days, today last
+    stats_summary: Summary, // Over the same days as daily_totals
+    first_focus: Vec<(NaiveDate, DateTime<Local>)>, // Each day's first session start
+    break_counts: (usize, usize), // Breaks taken and skipped over the same days as daily_totals
+    all_time_totals: (usize, i64), // Sessions and focus seconds since the first session
+    label_totals: Vec<(Option<String>, i64)>, // All-time focus seconds per label, largest first
+    tag_totals: Vec<(Option<String>, i64)>, // All-time focus seconds per project tag, largest first
+    hourly_focus: [i64; 24], // All-time focus seconds by local hour the session started
+    streaks: (u32, u32), // Current and longest run of days with a session
+    goal_screen_dismissed: Option<NaiveDate>, // Day Keep going was pressed on the goal screen
+    editing_note: Option<(i64, String)>, // Session id and the note being edited
+    delete_pending: Option<WorkSession>, // Picked for deletion in history, waiting for confirmation
+    import_format: Option<ImportFormat>, // None = detect from headers
+    data_status: Option<String>, // Outcome of the last import or export
+    sound_warning: Option<String>, // Shown in settings when the custom sound can't be played
+    chime_replay: Option<(Instant, u32, Chime)>, // Next repeat of the completion chime and repeats left; None once acknowledged
+    suspect_sessions: Option<Vec<WorkSession>>, // None until a duration check has run
+    retention_pending: Option<u32>, // Retention days waiting for the user to confirm deletion
+    reset_confirmation: Option<String>, // Typed so far while Reset all data is asking to confirm
+    last_prune: Option<Instant>,
     break_window_minimized: bool,
-    tray_icon: Option<TrayIcon>,
+    monitor_size: Option<egui::Vec2>, // Last seen size of the window's monitor, to notice dock/undock
+    window_geometry: Option<WindowGeometry>, // Last normal-window position and size, saved at quit
+    restore_check_pending: bool, // Saved geometry not yet checked against the current monitor
+    window_hidden: bool, // Hidden from the dock during work, see Settings::hide_during_work
+    showing_break_screen: bool, // Screen drawn last frame, to notice mode switches
+    fade_started: Option<Instant>, // Set while the new screen fades in
+    break_suggestions: BreakSuggestions,
+    current_suggestion: String,
+    webhook: Webhook,
+    ambient: AmbientPlayer,
+    #[cfg(unix)]
+    state_socket: Option<StateSocket>, // Set while Settings::state_socket_enabled
+    hotkeys: Option<GlobalHotkeys>, // Set by connect_desktop
+    #[cfg(unix)]
+    published_state: Option<(PomodoroMode, TimerState)>, // Last state sent to the state socket
+    tray_icon: Option<TrayIcon>, // Set by connect_desktop, unless the platform refused one
+    tray_menu: TrayMenu,
 }
 
-impl Default for PomodoroApp {
-    fn default() -> Self {
-        let db = Database::new().expect("Failed to initialize database");
-        let today_session_count = db.get_sessions_count_for_today()
-            .unwrap_or(0);
+impl PomodoroApp {
+    fn new(mut db: Database, settings: Settings, settings_backend: SettingsBackend, clock: Arc<dyn Clock>) -> Self {
+        db.set_day_boundary(settings.day_boundary);
+        db.set_day_start_hour(settings.day_start_hour);
+        let (today_session_count, today_focus_seconds) = db.get_today_totals()
+            .unwrap_or((0, 0));
+        let today_goal_count = db.get_today_goal_count().unwrap_or(0);
+        let cycle_position = db.get_cycle_position().unwrap_or(0);
+        let interrupted_work = db.take_interrupted_work().unwrap_or_else(|e| {
+            eprintln!("Failed to read interrupted session: {}", e);
+            None
+        });
+        
+        let focus_contract = db.get_focus_contract().unwrap_or_else(|e| {
+            eprintln!("Failed to read focus contract: {}", e);
+            None
+        });
         
-        // Create tray icon for menu bar timer display
-        let tray_icon = TrayIconBuilder::new()
-            .with_title("25:00")
-            .with_tooltip("Pocket Flow - Pomodoro Timer")
-            .build()
-            .ok();
+        let break_suggestions = BreakSuggestions::new(db.data_dir());
+        let session_writer = SessionWriter::start(&db);
+        let window_geometry = db.get_window_geometry().unwrap_or(None);
         
-        Self {
+        let mut app = Self {
             mode: PomodoroMode::Work,
             state: TimerState::Stopped,
-            remaining_seconds: WORK_DURATION,
-            last_tick: None,
-            work_session_start: None,
+            work_duration: settings.work_seconds(),
+            break_duration: settings.break_seconds(),
+            long_break_duration: settings.long_break_seconds(),
+            remaining_seconds: settings.work_seconds(),
+            completion_fired: false,
+            wind_down_fired: false,
+            work_period: None,
+            current_label: String::new(),
+            current_tag: None,
+            current_note: String::new(),
+            known_labels: Vec::new(),
             today_session_count,
+            today_goal_count,
+            today_focus_seconds,
+            counted_day: db.current_day(),
+            last_day_check: None,
+            cycle_position,
+            long_break_on_demand: false,
+            goal_pending_session: None,
+            goal_pending_write: false,
+            focus_contract,
+            contract_input: DEFAULT_CONTRACT_SESSIONS,
+            contract_prompt_dismissed: false,
+            welcome_back: false,
+            skip_reason_prompt: false,
+            quit_prompt: false,
+            quit_confirmed: false,
+            interruption_note: None,
+            idle_paused: false,
+            last_idle_check: None,
+            break_ready: false,
+            break_started_at: None,
+            pending_break: None,
             db,
+            session_writer,
+            clock,
+            transparent_window: settings.wants_transparent_window(),
+            settings,
+            settings_backend,
+            settings_open: false,
+            stats_open: false,
+            history: Vec::new(),
+            history_complete: false,
+            break_history: Vec::new(),
+            today_sessions: Vec::new(),
+            daily_totals: Vec::new(),
+            week_totals: Vec::new(),
+            stats_summary: Summary::default(),
+            first_focus: Vec::new(),
+            break_counts: (0, 0),
+            all_time_totals: (0, 0),
+            label_totals: Vec::new(),
+            tag_totals: Vec::new(),
+            hourly_focus: [0; 24],
+            streaks: (0, 0),
+            goal_screen_dismissed: None,
+            editing_note: None,
+            delete_pending: None,
+            import_format: None,
+            data_status: None,
+            sound_warning: None,
+            chime_replay: None,
+            suspect_sessions: None,
+            retention_pending: None,
+            reset_confirmation: None,
+            last_prune: None,
             break_window_minimized: false,
-            tray_icon,
+            monitor_size: None,
+            window_geometry,
+            restore_check_pending: window_geometry.is_some(),
+            window_hidden: false,
+            showing_break_screen: false,
+            fade_started: None,
+            break_suggestions,
+            current_suggestion: String::new(),
+            webhook: Webhook::new(),
+            ambient: AmbientPlayer::new(),
+            #[cfg(unix)]
+            state_socket: None,
+            hotkeys: None,
+            #[cfg(unix)]
+            published_state: None,
+            tray_icon: None,
+            tray_menu: TrayMenu::new(),
+        };
+        
+        // Pick up a work session left running or paused at the last quit, paused
+        if let Some(remaining) = interrupted_work.filter(|r| *r > 0 && *r <= app.work_duration) {
+            app.state = TimerState::Paused;
+            app.remaining_seconds = remaining;
+            // Backdated so the saved duration leaves out the time the app was closed
+            app.work_period = Some(WorkPeriod::restored(
+                app.clock.now_utc() - chrono::Duration::seconds((app.work_duration - remaining) as i64),
+                app.work_duration,
+                app.clock.now_instant(),
+            ));
         }
+        
+        app.check_custom_sound();
+        app.check_welcome_back();
+        #[cfg(unix)]
+        app.apply_state_socket();
+        app.prune_old_sessions();
+        app.load_known_labels();
+        app.refresh_streaks();
+        app.update_menu_bar();
+        app
     }
 }
 
 impl PomodoroApp {
+    /// Menu bar icon and global shortcuts, which need the running event loop. Kept out of
+    /// new so the app can be built without them.
+    fn connect_desktop(&mut self, ctx: &egui::Context) {
+        self.tray_icon = tray::build_tray_icon(&self.tray_menu);
+        self.hotkeys = Some(GlobalHotkeys::new(ctx));
+        self.apply_hotkeys();
+        self.update_menu_bar();
+    }
+    
     fn start(&mut self, ctx: &egui::Context) {
-        self.state = TimerState::Running;
-        self.last_tick = Some(Instant::now());
+        // Resuming sets a fresh deadline from the frozen remaining time
+        self.state = TimerState::running_for(self.remaining_seconds, self.clock.now_instant());
+        self.interruption_note = None;
+        self.idle_paused = false;
+        self.chime_replay = None;
         
-        // Track work session start time
-        if self.mode == PomodoroMode::Work && self.work_session_start.is_none() {
-            self.work_session_start = Some(Utc::now());
-            // Minimize window when starting work session
-            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+        // Track work session start time, set once per period; resuming only ends the pause
+        if self.mode == PomodoroMode::Work {
+            match &mut self.work_period {
+                Some(period) => period.resume(self.clock.now_instant()),
+                None => {
+                    self.work_period = Some(WorkPeriod::start(self.clock.now_utc(), self.work_duration));
+                    self.welcome_back = false;
+                    // Working on instead of taking the offered break forfeits its goal credit
+                    if self.break_ready {
+                        self.break_ready = false;
+                        self.goal_pending_session = None;
+                        self.goal_pending_write = false;
+                    }
+                    // Get the window out of the way when starting work session
+                    self.tuck_away_window(ctx);
+                }
+            }
         }
         
         self.update_menu_bar();
     }
 
     fn pause(&mut self) {
+        if let Some(period) = &mut self.work_period {
+            period.pause(self.clock.now_instant());
+        }
         self.state = TimerState::Paused;
-        self.last_tick = None;
         self.update_menu_bar();
     }
 
-    fn restart(&mut self) {
+    /// Abandon the current period and go back to Stopped at full duration
+    fn stop(&mut self) {
         self.state = TimerState::Stopped;
-        self.remaining_seconds = match self.mode {
-            PomodoroMode::Work => WORK_DURATION,
-            PomodoroMode::Break => BREAK_DURATION,
-        };
-        self.last_tick = None;
+        self.interruption_note = None;
+        self.idle_paused = false;
+        self.remaining_seconds = self.duration_of(self.mode);
+        self.completion_fired = false;
+        self.wind_down_fired = false;
         
         // Reset work session tracking (uncompleted sessions are not saved)
-        self.work_session_start = None;
+        self.work_period = None;
         self.update_menu_bar();
     }
 
+    /// Throw away progress and immediately run the period again from full duration
+    fn restart(&mut self, ctx: &egui::Context) {
+        self.stop();
+        self.start(ctx);
+    }
+
     fn start_break(&mut self, ctx: &egui::Context) {
-        self.mode = PomodoroMode::Break;
-        self.remaining_seconds = BREAK_DURATION;
-        self.state = TimerState::Running;
-        self.last_tick = Some(Instant::now());
+        // A full cycle of work sessions earns a long break
+        let mode = if self.cycle_position >= SESSIONS_PER_CYCLE {
+            PomodoroMode::LongBreak
+        } else {
+            PomodoroMode::Break
+        };
+        self.long_break_on_demand = false;
+        // Optionally ease into the cycle with a windowed first break
+        let fullscreen = !(self.settings.gentle_first_break && self.cycle_position == 1);
+        
+        // Count down in the normal window first so a thought can be finished
+        if fullscreen && self.settings.break_grace_secs > 0 {
+            self.state = TimerState::Stopped;
+            self.pending_break =
+                Some((mode, self.clock.now_instant() + Duration::from_secs(self.settings.break_grace_secs)));
+            self.show_window(ctx);
+            self.update_menu_bar();
+            return;
+        }
+        self.enter_break(ctx, mode, fullscreen);
+    }
+    
+    /// Go fullscreen once the grace countdown runs out
+    fn update_pending_break(&mut self, ctx: &egui::Context) {
+        let Some((mode, starts_at)) = self.pending_break else { return };
+        // Held while the user is picking a skip reason
+        if self.skip_reason_prompt {
+            return;
+        }
+        if self.clock.now_instant() >= starts_at {
+            self.enter_break(ctx, mode, true);
+        } else {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+    }
+    
+    fn delay_pending_break(&mut self) {
+        if self.settings.strict_mode {
+            return;
+        }
+        if let Some((_, starts_at)) = &mut self.pending_break {
+            *starts_at += BREAK_DELAY;
+        }
+    }
+    
+    /// Skip a break that hasn't gone fullscreen yet, same as skipping it once started
+    fn skip_pending_break(&mut self, ctx: &egui::Context, reason: Option<&str>) {
+        if self.settings.strict_mode {
+            return;
+        }
+        if let Some((mode, _)) = self.pending_break.take() {
+            self.mode = mode;
+            self.skip_break(ctx, reason);
+        }
+    }
+    
+    /// Skip the running or pending break, first asking why when Settings::ask_skip_reason is on
+    fn request_skip(&mut self, ctx: &egui::Context) {
+        if self.settings.strict_mode {
+            return;
+        }
+        if self.settings.ask_skip_reason {
+            self.skip_reason_prompt = true;
+        } else {
+            self.confirm_skip(ctx, None);
+        }
+    }
+    
+    fn confirm_skip(&mut self, ctx: &egui::Context, reason: Option<&str>) {
+        self.skip_reason_prompt = false;
+        if self.pending_break.is_some() {
+            self.skip_pending_break(ctx, reason);
+        } else {
+            self.skip_break(ctx, reason);
+        }
+    }
+    
+    /// Long break right now, whatever the cycle position. The work session in progress is
+    /// dropped like with Stop.
+    fn take_long_break(&mut self, ctx: &egui::Context) {
+        if self.mode.is_break() {
+            return;
+        }
+        self.long_break_on_demand = true;
+        self.enter_break(ctx, PomodoroMode::LongBreak, true);
+    }
+    
+    fn enter_break(&mut self, ctx: &egui::Context, mode: PomodoroMode, fullscreen: bool) {
+        self.pending_break = None;
+        self.break_ready = false;
+        self.skip_reason_prompt = false;
+        self.mode = mode;
+        self.remaining_seconds = self.duration_of(self.mode);
+        self.state = TimerState::running_for(self.remaining_seconds, self.clock.now_instant());
+        self.break_started_at = Some(self.clock.now_utc());
+        self.completion_fired = false;
         
         // Reset work session tracking
-        self.work_session_start = None;
+        self.work_period = None;
+        
+        self.current_suggestion = self.break_suggestions.next();
         
-        // Reset minimized state and request fullscreen
-        self.break_window_minimized = false;
-        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+        // Reset minimized state and request fullscreen, or show the break windowed
+        self.break_window_minimized = !fullscreen;
+        self.show_window(ctx);
+        if fullscreen {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+        }
         self.update_menu_bar();
     }
 
     fn start_work(&mut self, ctx: &egui::Context) {
         self.mode = PomodoroMode::Work;
-        self.remaining_seconds = WORK_DURATION;
+        self.remaining_seconds = self.work_duration;
         self.state = TimerState::Stopped;
-        self.last_tick = None;
+        self.completion_fired = false;
+        self.wind_down_fired = false;
         
         // Exit fullscreen
         ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
         self.update_menu_bar();
     }
 
-    fn skip_break(&mut self, ctx: &egui::Context) {
+    fn skip_break(&mut self, ctx: &egui::Context, reason: Option<&str>) {
+        // Strict mode requires the full break to elapse
+        if self.settings.strict_mode {
+            return;
+        }
+        
+        self.record_break(true, reason);
+        
+        // A skipped break forfeits the goal credit of the session before it
+        self.goal_pending_session = None;
+        self.goal_pending_write = false;
+        
+        // Skipping a long break still ends the cycle
+        self.end_long_break();
+        
         self.mode = PomodoroMode::Work;
-        self.remaining_seconds = WORK_DURATION;
-        self.state = TimerState::Running;
-        self.last_tick = Some(Instant::now());
+        self.remaining_seconds = self.work_duration;
+        self.state = TimerState::running_for(self.work_duration, self.clock.now_instant());
+        self.completion_fired = false;
+        self.wind_down_fired = false;
         
         // Track new work session start time
-        self.work_session_start = Some(Utc::now());
+        self.work_period = Some(WorkPeriod::start(self.clock.now_utc(), self.work_duration));
         
-        // Exit fullscreen and minimize window
+        // Exit fullscreen and get the window out of the way
         ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
-        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+        self.tuck_away_window(ctx);
         self.update_menu_bar();
     }
 
+    /// Minimize the window for a work session, or hide it entirely (tray only) when configured
+    fn tuck_away_window(&mut self, ctx: &egui::Context) {
+        if self.settings.hide_during_work {
+            self.window_hidden = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        } else {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+        }
+    }
+    
+    fn show_window(&mut self, ctx: &egui::Context) {
+        if self.window_hidden {
+            self.window_hidden = false;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+    }
+    
+    /// Keep a fullscreen break visible when a monitor is plugged or unplugged under it
+    fn check_display_change(&mut self, ctx: &egui::Context) {
+        let monitor_size = ctx.input(|i| i.viewport().monitor_size);
+        let changed = self.monitor_size.is_some() && monitor_size != self.monitor_size;
+        self.monitor_size = monitor_size;
+        
+        let fullscreen_break = self.mode.is_break()
+            && self.remaining_seconds > 0
+            && !self.break_window_minimized;
+        if !changed || !fullscreen_break {
+            return;
+        }
+        
+        match self.settings.display_change_action {
+            DisplayChangeAction::Refullscreen => {
+                // Leaving and re-entering fullscreen resizes the window to its current display
+                ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            }
+            DisplayChangeAction::Windowed => self.minimize_break_window(ctx),
+        }
+    }
+    
     fn minimize_break_window(&mut self, ctx: &egui::Context) {
         // Exit fullscreen and mark as minimized
         self.break_window_minimized = true;
@@ -216,208 +705,959 @@ impl PomodoroApp {
     }
 
     fn update_timer(&mut self, ctx: &egui::Context) {
-        if self.state == TimerState::Running {
-            if let Some(last_tick) = self.last_tick {
-                let elapsed = last_tick.elapsed();
+        self.update_pending_break(ctx);
+        self.update_chime_replay(ctx);
+        self.check_day_rollover();
+        
+        if let Some(remaining) = self.state.remaining_at(self.clock.now_instant()) {
+            if remaining != self.remaining_seconds {
+                self.remaining_seconds = remaining;
                 
-                if elapsed >= Duration::from_secs(1) {
-                    self.last_tick = Some(Instant::now());
-                    
-                    if self.remaining_seconds > 0 {
-                        self.remaining_seconds -= 1;
-                    }
-                    
-                    // Update menu bar timer display
+                // Update menu bar timer display
+                self.update_menu_bar();
+            }
+            
+            self.check_wind_down();
+            
+            // Check if timer completed; the latch makes this fire once per period
+            // no matter how many frames observe zero
+            if self.remaining_seconds == 0 && !self.completion_fired {
+                self.completion_fired = true;
+                self.complete_period(ctx);
+            }
+        }
+        
+        // Wake up when the display next changes rather than every frame. The deadline keeps
+        // the time right however late this comes, e.g. while egui throttles a minimized window.
+        if let TimerState::Running { deadline } = self.state {
+            let left = deadline.saturating_duration_since(self.clock.now_instant());
+            let to_next_second = left - Duration::from_secs(left.as_secs());
+            ctx.request_repaint_after(if to_next_second.is_zero() { Duration::from_secs(1) } else { to_next_second });
+        }
+    }
+
+    /// Warn once per work period when it's about to end, so the user can reach a stopping point
+    fn check_wind_down(&mut self) {
+        let lead = self.settings.wind_down_lead_secs;
+        if self.mode != PomodoroMode::Work
+            || !self.settings.wind_down_enabled
+            || self.wind_down_fired
+            || self.remaining_seconds == 0
+            || self.remaining_seconds > lead
+        {
+            return;
+        }
+        
+        self.wind_down_fired = true;
+        let lead_text = if lead.is_multiple_of(60) {
+            let minutes = lead / 60;
+            format!("{} minute{}", minutes, if minutes == 1 { "" } else { "s" })
+        } else {
+            format!("{} seconds", lead)
+        };
+        notifications::send(
+            "Wrapping up soon",
+            &format!("Break starts in {}. Find a good stopping point.", lead_text),
+        );
+    }
+
+    fn play_chime(&self, chime: Chime) {
+        audio::play_completion_sound(
+            self.completion_sound_path(),
+            chime,
+            self.settings.sound_volume,
+            Duration::from_millis(self.settings.sound_fade_in_ms),
+        );
+    }
+    
+    /// Repeat the completion chime until any click or key press, see Settings::chime_repeats
+    fn update_chime_replay(&mut self, ctx: &egui::Context) {
+        let Some((due, left, chime)) = self.chime_replay else { return };
+        let acknowledged = ctx.input(|i| {
+            i.pointer.any_pressed() || i.events.iter().any(|e| matches!(e, egui::Event::Key { pressed: true, .. }))
+        });
+        if acknowledged || !self.settings.sound_enabled {
+            self.chime_replay = None;
+            return;
+        }
+        
+        let now = self.clock.now_instant();
+        if now >= due {
+            self.play_chime(chime);
+            self.chime_replay = (left > 1).then(|| (now + Duration::from_secs(self.settings.chime_repeat_secs), left - 1, chime));
+        }
+        if let Some((due, _, _)) = self.chime_replay {
+            ctx.request_repaint_after(due.saturating_duration_since(now));
+        }
+    }
+    
+    /// Save the work session in progress. It's counted toward today, the goal and the cycle
+    /// right away; the write itself happens on the session writer's thread and finishes in
+    /// handle_saved_sessions.
+    fn save_work_period(&mut self, ended_early: bool) {
+        let Some(period) = &self.work_period else { return };
+        let mut session = period.finish(self.clock.now_utc(), self.clock.now_instant());
+        let label = self.current_label.trim();
+        session.label = (!label.is_empty()).then(|| label.to_string());
+        session.tag = self.current_tag.as_deref().map(str::trim).filter(|t| !t.is_empty()).map(str::to_string);
+        // Taken rather than kept like the label, so the next session starts with a blank note
+        let typed = std::mem::take(&mut self.current_note);
+        let typed = typed.trim();
+        if !typed.is_empty() {
+            session.note = Some(match session.note.take() {
+                Some(interruptions) => format!("{}\n{}", typed, interruptions),
+                None => typed.to_string(),
+            });
+        }
+        // Held back until the break is taken; continuous mode has no break to wait for
+        let defer_goal = self.settings.goal_requires_break && !self.settings.continuous_mode;
+        session.counts_toward_goal = !defer_goal;
+        session.end_reason = if ended_early {
+            EndReason::EndedEarly
+        } else if period.was_resumed() {
+            EndReason::Interrupted
+        } else if session.duration_seconds < period.planned_seconds() as i64 - CLOCK_TOLERANCE {
+            // The timer runs on a monotonic clock, so a session shorter than the work
+            // period by the wall clock means the clock was set back underneath it
+            EndReason::ClockJump
+        } else {
+            EndReason::Completed
+        };
+        
+        let webhook_url = self.settings.webhook_url.trim();
+        if !webhook_url.is_empty() {
+            self.webhook.send_session_completed(webhook_url, &session);
+        }
+        
+        self.today_session_count += 1;
+        self.today_focus_seconds = self.today_focus_seconds.saturating_add(session.duration_seconds);
+        // A held-back session is checked once its break has been taken instead
+        if !defer_goal {
+            self.today_goal_count += 1;
+            self.check_session_goal();
+        }
+        // Continuous mode has no long breaks to count toward
+        if !self.settings.continuous_mode {
+            self.set_cycle_position(self.cycle_position + 1);
+        }
+        self.goal_pending_write = defer_goal;
+        self.session_writer.save(session);
+    }
+    
+    /// Take Break during work: end the session now and start the break. Sessions shorter than
+    /// Settings::min_session_seconds are dropped like with Stop; longer ones are saved as
+    /// ended early and count toward today like a full one.
+    fn end_work_early(&mut self, ctx: &egui::Context) {
+        let focused = self.work_period.as_ref().map(|period| {
+            period.finish(self.clock.now_utc(), self.clock.now_instant()).duration_seconds
+        });
+        if focused.is_some_and(|seconds| seconds >= self.settings.min_session_seconds as i64) {
+            self.save_work_period(true);
+        }
+        self.work_period = None;
+        self.interruption_note = None;
+        self.idle_paused = false;
+        self.wind_down_fired = false;
+        self.start_break(ctx);
+    }
+    
+    fn complete_period(&mut self, ctx: &egui::Context) {
+        #[cfg(unix)]
+        if let Some(socket) = &self.state_socket {
+            socket.send("complete", self.mode.as_str(), self.state.as_str(), 0);
+        }
+        
+        if self.settings.sound_enabled {
+            let chime = if self.mode.is_break() { Chime::BreakDone } else { Chime::WorkDone };
+            self.play_chime(chime);
+            self.chime_replay = (self.settings.chime_repeats > 0).then(|| {
+                (self.clock.now_instant() + Duration::from_secs(self.settings.chime_repeat_secs), self.settings.chime_repeats, chime)
+            });
+        }
+        
+        let finished = self.mode;
+        match self.mode {
+            PomodoroMode::Work => {
+                self.save_work_period(false);
+                
+                if self.settings.continuous_mode {
+                    // Roll straight into the next work session
+                    self.stop();
+                    self.start(ctx);
+                } else if !self.settings.auto_break {
+                    // Wait for Take a Break, like the end of a break waits for the next session
+                    self.stop();
+                    self.break_ready = true;
+                    self.show_window(ctx);
                     self.update_menu_bar();
-                    
-                    // Check if timer completed
-                    if self.remaining_seconds == 0 {
-                        match self.mode {
-                            PomodoroMode::Work => {
-                                // Save completed work session
-                                if let Some(start_time) = self.work_session_start {
-                                    let completed_at = Utc::now();
-                                    let session = WorkSession::new(start_time, completed_at);
-                                    
-                                    if let Err(e) = self.db.save_work_session(&session) {
-                                        eprintln!("Failed to save work session: {}", e);
-                                    } else {
-                                        // Increment session count on successful save
-                                        self.today_session_count += 1;
-                                    }
-                                }
-                                
-                                // Work period done, start break
-                                self.start_break(ctx);
-                            }
-                            PomodoroMode::Break => {
-                                // Break done, stop and wait for user
-                                self.state = TimerState::Stopped;
-                                self.last_tick = None;
-                                // Update menu bar to show break is done
-                                self.update_menu_bar();
-                                // Exit fullscreen when break ends (only if not already minimized)
-                                if !self.break_window_minimized {
-                                    ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
-                                }
-                            }
+                } else {
+                    // Work period done, start break
+                    self.start_break(ctx);
+                }
+            }
+            PomodoroMode::Break | PomodoroMode::LongBreak => {
+                self.record_break(false, None);
+                
+                // The break was taken in full, so the session before it now counts
+                if let Some(id) = self.goal_pending_session.take() {
+                    if let Err(e) = self.db.credit_session_to_goal(id) {
+                        eprintln!("Failed to credit session to goal: {}", e);
+                    } else {
+                        // Recounted rather than added to, in case the session was yesterday's
+                        self.refresh_today_totals();
+                        self.check_session_goal();
+                        if self.stats_open {
+                            self.refresh_stats();
                         }
                     }
                 }
+                
+                // A finished long break starts a new cycle
+                self.end_long_break();
+                self.skip_reason_prompt = false;
+                
+                if self.settings.auto_start_work {
+                    // Straight into the next session, which also gets the window out of the way
+                    self.start_work(ctx);
+                    self.start(ctx);
+                } else {
+                    // Break done, stop and wait for user
+                    self.state = TimerState::Stopped;
+                    // Update menu bar to show break is done
+                    self.update_menu_bar();
+                    // Exit fullscreen when break ends (only if not already minimized)
+                    if !self.break_window_minimized {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
+                    }
+                    match self.settings.break_end_action {
+                        BreakEndAction::Background => {}
+                        BreakEndAction::Focus => self.show_window(ctx),
+                        BreakEndAction::Attention => ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(
+                            egui::UserAttentionType::Informational,
+                        )),
+                    }
+                }
             }
-            
-            // Request repaint for smooth countdown
-            ctx.request_repaint();
+        }
+        
+        if self.settings.notifications_enabled {
+            self.notify_period_end(finished);
+        }
+    }
+    
+    /// Sent after the period's bookkeeping, so today's count includes a session that just ended
+    fn notify_period_end(&self, finished: PomodoroMode) {
+        let count = self.today_session_count;
+        let done_today = format!("{} pomodoro{} done today.", count, if count == 1 { "" } else { "s" });
+        if finished.is_break() && self.state.is_running() {
+            notifications::send("Break's over", &format!("Next session started. {}", done_today));
+        } else if finished.is_break() {
+            notifications::send("Break's over", &format!("Ready for the next session? {}", done_today));
+        } else if self.mode.is_break() || self.pending_break.is_some() {
+            notifications::send("Break time!", &done_today);
+        } else {
+            notifications::send("Session complete", &done_today);
         }
     }
 
+    fn duration_of(&self, mode: PomodoroMode) -> u64 {
+        match mode {
+            PomodoroMode::Work => self.work_duration,
+            PomodoroMode::Break => self.break_duration,
+            PomodoroMode::LongBreak => self.long_break_duration,
+        }
+    }
+    
+    /// Pick up edited durations. A period already under way keeps its length; a stopped
+    /// timer shows the new one straight away.
+    fn apply_durations(&mut self) {
+        self.work_duration = self.settings.work_seconds();
+        self.break_duration = self.settings.break_seconds();
+        self.long_break_duration = self.settings.long_break_seconds();
+        if self.state == TimerState::Stopped {
+            self.remaining_seconds = self.duration_of(self.mode);
+            self.update_menu_bar();
+        }
+    }
+    
     fn format_time(&self) -> String {
         let minutes = self.remaining_seconds / 60;
         let seconds = self.remaining_seconds % 60;
         format!("{:02}:{:02}", minutes, seconds)
     }
     
+    /// The big on-screen countdown. With Settings::final_minute_seconds the last minute of a
+    /// running or paused period shows just the seconds, larger and in the accent color.
+    fn timer_text(&self, size: f32, theme: &Theme) -> egui::RichText {
+        let final_minute = self.settings.final_minute_seconds
+            && self.state != TimerState::Stopped
+            && self.remaining_seconds > 0
+            && self.remaining_seconds < 60;
+        if final_minute {
+            egui::RichText::new(self.remaining_seconds.to_string())
+                .size(size * FINAL_MINUTE_SCALE)
+                .monospace()
+                .color(theme.accent)
+        } else {
+            egui::RichText::new(self.format_time())
+                .size(size)
+                .monospace()
+                .color(theme.background)
+        }
+    }
+    
+    fn perform(&mut self, action: ControlAction, ctx: &egui::Context) {
+        match action {
+            ControlAction::Start | ControlAction::Resume => self.start(ctx),
+            ControlAction::Pause => self.pause(),
+            ControlAction::Restart => self.restart(ctx),
+            ControlAction::Stop => self.stop(),
+            ControlAction::StartNewTimer => self.start_work(ctx),
+            ControlAction::SkipBreak => self.request_skip(ctx),
+            ControlAction::DelayBreak => self.delay_pending_break(),
+            ControlAction::TakeBreak => self.start_break(ctx),
+            ControlAction::EndEarly => self.end_work_early(ctx),
+            ControlAction::Interrupt => self.interrupt(),
+            ControlAction::Minimize => self.minimize_break_window(ctx),
+        }
+    }
+    
+    /// Space starts or pauses and R restarts, unless a text field has focus. Space is consumed
+    /// so a focused button doesn't also take it as a click.
+    fn work_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Space)) {
+            self.toggle_start_pause(ctx);
+        }
+        if self.state != TimerState::Stopped && ctx.input(|i| i.key_pressed(egui::Key::R)) {
+            self.restart(ctx);
+        }
+    }
+    
+    /// Escape precedence: dismiss the quit prompt, then close the settings window, then the
+    /// stats window; otherwise minimize a fullscreen break, or during work run the configured action
+    fn handle_escape(&mut self, ctx: &egui::Context) {
+        if self.quit_prompt {
+            self.quit_prompt = false;
+        } else if self.settings_open {
+            self.settings_open = false;
+        } else if self.stats_open {
+            self.stats_open = false;
+        } else if self.mode.is_break() {
+            if self.remaining_seconds > 0 && !self.break_window_minimized {
+                self.minimize_break_window(ctx);
+            }
+        } else {
+            match self.settings.escape_action {
+                EscapeAction::Nothing => {}
+                EscapeAction::Pause => {
+                    if self.state.is_running() {
+                        self.pause();
+                    }
+                }
+                EscapeAction::Minimize => ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true)),
+            }
+        }
+    }
+    
+    /// Save the break in progress. One skipped during its grace countdown never started, so it
+    /// has no length.
+    fn record_break(&mut self, skipped: bool, skip_reason: Option<&str>) {
+        let ended_at = self.clock.now_utc();
+        let started_at = self.break_started_at.take();
+        let duration_seconds = match started_at {
+            Some(_) => self.duration_of(self.mode).saturating_sub(self.remaining_seconds) as i64,
+            None => 0,
+        };
+        let session = BreakSession {
+            started_at: started_at.unwrap_or(ended_at),
+            ended_at,
+            duration_seconds,
+            long_break: self.mode == PomodoroMode::LongBreak,
+            skipped,
+            skip_reason: skip_reason.map(str::to_string),
+        };
+        if let Err(e) = self.db.save_break_session(&session) {
+            eprintln!("Failed to record break: {}", e);
+        }
+    }
+    
+    /// A scheduled long break starts a new cycle. An on-demand one only does when
+    /// Settings::on_demand_long_break_resets_cycle is set, otherwise progress is kept.
+    fn end_long_break(&mut self) {
+        if self.mode == PomodoroMode::LongBreak
+            && (!self.long_break_on_demand || self.settings.on_demand_long_break_resets_cycle)
+        {
+            self.set_cycle_position(0);
+        }
+        self.long_break_on_demand = false;
+    }
+    
+    fn set_cycle_position(&mut self, position: usize) {
+        self.cycle_position = position;
+        if let Err(e) = self.db.set_cycle_position(position) {
+            eprintln!("Failed to save cycle position: {}", e);
+        }
+    }
+    
+    /// Active profile's theme, otherwise the global one
+    fn theme(&self) -> &'static Theme {
+        let profile_theme = self
+            .active_profile()
+            .map(|profile| profile.theme.as_str())
+            .filter(|name| !name.is_empty());
+        Theme::by_name_for(profile_theme.unwrap_or(&self.settings.theme), self.settings.theme_mode)
+    }
+    
+    fn active_profile(&self) -> Option<&Profile> {
+        self.settings.profile_for(&self.current_label)
+    }
+    
+    /// Active profile's sound, otherwise the global one; None = built-in chime
+    fn completion_sound_path(&self) -> Option<std::path::PathBuf> {
+        let profile_sound = self.active_profile().map(|profile| profile.sound_file.trim()).filter(|path| !path.is_empty());
+        match profile_sound {
+            Some(path) => Some(path.into()),
+            None => self.settings.custom_sound_path(),
+        }
+    }
+    
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save(self.settings_backend, &self.db) {
+            eprintln!("Failed to save settings: {}", e);
+        }
+    }
+    
+    /// Opacity for the screen content, fading in the new screen after a work/break switch.
+    /// Repaints only while a fade is running.
+    fn screen_opacity(&mut self, ctx: &egui::Context) -> f32 {
+        let showing_break = self.mode.is_break();
+        if showing_break != self.showing_break_screen {
+            self.showing_break_screen = showing_break;
+            self.fade_started = (!self.settings.reduce_motion).then(|| self.clock.now_instant());
+        }
+        
+        let Some(started) = self.fade_started else { return 1.0 };
+        let progress = self.clock.now_instant().duration_since(started).as_secs_f32() / FADE_DURATION.as_secs_f32();
+        if progress >= 1.0 {
+            self.fade_started = None;
+            return 1.0;
+        }
+        ctx.request_repaint();
+        progress
+    }
+    
     fn update_menu_bar(&self) {
+        self.tray_menu.sync(self.mode, self.state, self.pending_break.is_some());
         if let Some(tray) = &self.tray_icon {
             let title = match self.state {
+                _ if self.pending_break.is_some() => "Break soon".to_string(),
+                TimerState::Stopped if self.break_ready => "Break Ready".to_string(),
                 TimerState::Stopped => {
                     match self.mode {
                         PomodoroMode::Work => "Ready".to_string(),
-                        PomodoroMode::Break => "Break Done".to_string(),
+                        PomodoroMode::Break | PomodoroMode::LongBreak => "Break Done".to_string(),
                     }
                 }
+                TimerState::Paused if self.mode.is_break() => format!("Break {} (Paused)", self.format_time()),
                 TimerState::Paused => format!("{} (Paused)", self.format_time()),
-                TimerState::Running => self.format_time(),
+                TimerState::Running { .. } if self.mode.is_break() => format!("Break {}", self.format_time()),
+                TimerState::Running { .. } => self.format_time(),
             };
-            let _ = tray.set_title(Some(&title));
+            tray.set_title(Some(&title));
+            
+            if let Err(e) = tray.set_tooltip(Some(self.tray_tooltip())) {
+                eprintln!("Failed to update tray tooltip: {}", e);
+            }
+        }
+    }
+    
+    /// "3 pomodoros · 1h15m today · Work 12:30"
+    fn tray_tooltip(&self) -> String {
+        let pomodoros = match self.today_session_count {
+            1 => "1 pomodoro".to_string(),
+            count => format!("{} pomodoros", self.settings.number_locale.integer(count as i64)),
+        };
+        let mode = match self.mode {
+            PomodoroMode::Work => "Work",
+            PomodoroMode::Break => "Break",
+            PomodoroMode::LongBreak => "Long break",
+        };
+        let timer = match self.state {
+            TimerState::Stopped => format!("{} ready", mode),
+            TimerState::Paused => format!("{} {} paused", mode, self.format_time()),
+            TimerState::Running { .. } => format!("{} {}", mode, self.format_time()),
+        };
+        format!("{} · {} today · {}", pomodoros, self.settings.number_locale.duration(self.today_focus_seconds), timer)
+    }
+    
+    /// Greet the user at launch when Settings::welcome_back_days have passed since their
+    /// last session. Checked only at startup, so regular use never sees it.
+    fn check_welcome_back(&mut self) {
+        if !self.settings.welcome_back_enabled {
+            return;
+        }
+        match self.db.get_last_session_day() {
+            Ok(Some(last)) => {
+                let days_away = (self.db.current_day() - last).num_days();
+                self.welcome_back = days_away >= self.settings.welcome_back_days as i64;
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to read last session: {}", e),
+        }
+    }
+    
+    /// Ambient loop plays only while a work session is running
+    fn update_ambient(&mut self) {
+        let working = self.mode == PomodoroMode::Work && self.state.is_running();
+        let source = if working { self.settings.ambient_source() } else { None };
+        self.ambient.set(source, self.settings.ambient_volume);
+    }
+    
+    /// Open or close the state socket to match the setting
+    #[cfg(unix)]
+    fn apply_state_socket(&mut self) {
+        if !self.settings.state_socket_enabled {
+            self.state_socket = None;
+            return;
+        }
+        if self.state_socket.is_some() {
+            return;
+        }
+        match StateSocket::start(self.db.data_dir()) {
+            Ok(socket) => {
+                self.state_socket = Some(socket);
+                // Sent again so connecting clients get the current state
+                self.published_state = None;
+            }
+            Err(e) => eprintln!("Failed to open state socket at {}: {}", StateSocket::path(self.db.data_dir()).display(), e),
+        }
+    }
+    
+    /// Send an event to the state socket whenever the mode or timer state has changed since
+    /// the last one. Checked each frame, so every way of changing the timer is covered.
+    #[cfg(unix)]
+    fn publish_state(&mut self) {
+        let Some(socket) = &self.state_socket else { return };
+        let current = (self.mode, self.state);
+        let Some((mode, state)) = self.published_state.replace(current) else {
+            socket.send("state", self.mode.as_str(), self.state.as_str(), self.remaining_seconds);
+            return;
+        };
+        if (mode, state) == current {
+            return;
+        }
+        
+        let event = if mode != self.mode {
+            "mode_change"
+        } else {
+            match (state, self.state) {
+                (TimerState::Paused, TimerState::Running { .. }) => "resume",
+                (_, TimerState::Running { .. }) => "start",
+                (_, TimerState::Paused) => "pause",
+                (_, TimerState::Stopped) => "stop",
+            }
+        };
+        socket.send(event, self.mode.as_str(), self.state.as_str(), self.remaining_seconds);
+    }
+    
+    fn load_known_labels(&mut self) {
+        match self.db.get_distinct_labels() {
+            Ok(labels) => self.known_labels = labels,
+            Err(e) => eprintln!("Failed to load task labels: {}", e),
+        }
+    }
+    
+    fn refresh_today_totals(&mut self) {
+        match self.db.get_today_totals() {
+            Ok((count, focus_seconds)) => {
+                self.today_session_count = count;
+                self.today_focus_seconds = focus_seconds;
+            }
+            Err(e) => eprintln!("Failed to count today's sessions: {}", e),
+        }
+        match self.db.get_today_goal_count() {
+            Ok(count) => self.today_goal_count = count,
+            Err(e) => eprintln!("Failed to count today's goal sessions: {}", e),
+        }
+        self.counted_day = self.db.current_day();
+        self.update_menu_bar();
+    }
+    
+    /// Recount today's sessions once the day changes under a long-running app, so the count
+    /// and the goal dots start again from zero. Checked every DAY_CHECK_INTERVAL.
+    fn check_day_rollover(&mut self) {
+        let now = self.clock.now_instant();
+        if self.last_day_check.is_some_and(|last| now.duration_since(last) < DAY_CHECK_INTERVAL) {
+            return;
+        }
+        self.last_day_check = Some(now);
+        
+        if self.db.current_day() == self.counted_day {
+            return;
+        }
+        self.refresh_today_totals();
+        if self.stats_open {
+            self.refresh_stats();
+        }
+    }
+    
+    /// "Break starting in 5…" in place of the timer, with Space to delay and Enter to skip.
+    /// Both are unavailable in strict mode.
+    fn break_grace_countdown(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, starts_at: Instant, theme: &Theme) {
+        let secs_left = starts_at.saturating_duration_since(self.clock.now_instant()).as_secs_f32().ceil() as u64;
+        ui.label(
+            egui::RichText::new(format!("Break starting in {}…", secs_left))
+                .size(28.0)
+                .color(theme.background)
+        );
+        ui.add_space(30.0);
+        
+        if self.settings.strict_mode {
+            return;
+        }
+        
+        let keys = if ctx.wants_keyboard_input() {
+            None
+        } else if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
+            Some(ControlAction::DelayBreak)
+        } else if ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
+            Some(ControlAction::SkipBreak)
+        } else {
+            None
+        };
+        if self.skip_reason_prompt {
+            self.skip_reason_buttons(ui, ctx, theme);
+            return;
+        }
+        
+        let clicked = control_buttons(ui, 100.0, &[ControlAction::DelayBreak, ControlAction::SkipBreak]);
+        ui.label(
+            egui::RichText::new("Space to delay, Enter to skip")
+                .size(12.0)
+                .color(theme.background)
+        );
+        
+        if let Some(action) = clicked.or(keys) {
+            self.perform(action, ctx);
+        }
+    }
+    
+    /// Bookkeeping that needs a session to be in the database: its id, its day's summary
+    /// and anything read back from the table
+    fn handle_saved_sessions(&mut self) {
+        let outcomes = self.session_writer.finished();
+        if outcomes.is_empty() {
+            return;
+        }
+        for outcome in outcomes {
+            if let Some(e) = outcome.error {
+                eprintln!("Failed to save work session: {}", e);
+                continue;
+            }
+            if let Err(e) = self.db.invalidate_session_day(&outcome.session) {
+                eprintln!("Failed to update daily summary: {}", e);
+            }
+            // Only a session held back for its break starts out not counting toward the goal
+            if self.goal_pending_write && !outcome.session.counts_toward_goal {
+                self.goal_pending_write = false;
+                self.goal_pending_session = outcome.session.id;
+            }
+            if outcome.session.label.is_some() {
+                self.load_known_labels();
+            }
+        }
+        if self.stats_open {
+            self.refresh_stats();
+        } else {
+            self.refresh_streaks();
+        }
+    }
+    
+    /// Best-effort save of anything that would otherwise be lost on quit: delivers queued
+    /// webhooks (bounded by SHUTDOWN_FLUSH_TIMEOUT) and remembers an unfinished work session
+    fn flush_on_exit(&mut self) {
+        let interrupted = (self.mode == PomodoroMode::Work
+            && self.state != TimerState::Stopped
+            && self.remaining_seconds > 0)
+            .then_some(self.remaining_seconds);
+        if let Err(e) = self.db.set_interrupted_work(interrupted) {
+            eprintln!("Failed to save timer state: {}", e);
+        }
+        self.save_window_geometry();
+        // Text fields save when they lose focus, which never happens if the app quits first
+        self.save_settings();
+        
+        self.session_writer.flush();
+        self.handle_saved_sessions();
+        
+        // Removes the socket file so clients see the app is gone
+        #[cfg(unix)]
+        {
+            self.state_socket = None;
+        }
+        
+        let undelivered = self.webhook.flush(SHUTDOWN_FLUSH_TIMEOUT);
+        if undelivered > 0 {
+            eprintln!("Quitting with {} webhook deliveries still pending", undelivered);
         }
     }
 }
 
 impl eframe::App for PomodoroApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.flush_on_exit();
+    }
+    
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        if self.transparent_window {
+            egui::Rgba::TRANSPARENT.to_array()
+        } else {
+            // eframe's default
+            egui::Color32::from_rgba_unmultiplied(12, 12, 12, 180).to_normalized_gamma_f32()
+        }
+    }
+    
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.handle_tray_events(ctx);
+        self.handle_hotkey_events(ctx);
+        self.check_close_request(ctx);
+        self.prune_if_due();
+        self.handle_saved_sessions();
+        self.check_idle();
         self.update_timer(ctx);
+        #[cfg(unix)]
+        self.publish_state();
+        self.update_ambient();
+        self.check_display_change(ctx);
+        self.track_window_geometry(ctx);
+        
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.handle_escape(ctx);
+        }
         
         // Apply custom theme
-        ctx.style_mut(|style| {
-            // Set overall background color to main dark blue
-            style.visuals.panel_fill = COLOR_MAIN;
-            
-            // Set text colors to white/light
-            style.visuals.override_text_color = Some(COLOR_BACKGROUND);
-            
-            // Button styling - inverted (dark inactive, light hover)
-            style.visuals.widgets.inactive.weak_bg_fill = COLOR_SECONDARY_DARK;
-            style.visuals.widgets.inactive.bg_fill = COLOR_SECONDARY_DARK;
-            style.visuals.widgets.inactive.fg_stroke.color = COLOR_BACKGROUND;
-            
-            style.visuals.widgets.hovered.weak_bg_fill = COLOR_SECONDARY;
-            style.visuals.widgets.hovered.bg_fill = COLOR_SECONDARY;
-            style.visuals.widgets.hovered.fg_stroke.color = COLOR_MAIN;
-            
-            style.visuals.widgets.active.weak_bg_fill = COLOR_SECONDARY;
-            style.visuals.widgets.active.bg_fill = COLOR_SECONDARY;
-            style.visuals.widgets.active.fg_stroke.color = COLOR_MAIN;
-            
-            // Rounding for buttons
-            style.visuals.widgets.inactive.rounding = egui::Rounding::same(8.0);
-            style.visuals.widgets.hovered.rounding = egui::Rounding::same(8.0);
-            style.visuals.widgets.active.rounding = egui::Rounding::same(8.0);
-            
-            // Button padding
-            style.spacing.button_padding = egui::vec2(16.0, 8.0);
-        });
+        let theme = self.theme();
+        apply_theme(ctx, theme);
+        
+        // Semi-transparent background, except for the fullscreen break which stays solid.
+        // Only the fill gets alpha so text remains opaque.
+        let fullscreen_break = self.mode.is_break()
+            && self.remaining_seconds > 0
+            && !self.break_window_minimized;
+        if self.transparent_window && !fullscreen_break {
+            let opacity = self.settings.window_opacity.clamp(0.0, 1.0);
+            ctx.style_mut(|style| style.visuals.panel_fill = theme.main.gamma_multiply(opacity));
+        }
 
+        let content_opacity = self.screen_opacity(ctx);
+        
         if self.mode == PomodoroMode::Work {
             // Normal window for work period
             egui::CentralPanel::default().show(ctx, |ui| {
+                ui.multiply_opacity(content_opacity);
+                // Filled in once the countdown is placed, so the ring sits behind the panel's content
+                let ring_slot = ui.painter().add(egui::Shape::Noop);
+                
+                // Settings and stats buttons pinned to the top-right corner, outside the centered layout
+                let gear_rect = egui::Rect::from_min_size(
+                    ui.max_rect().right_top() - egui::vec2(32.0, 0.0),
+                    egui::vec2(32.0, 28.0),
+                );
+                if ui.put(gear_rect, egui::Button::new("⚙").frame(false)).clicked() {
+                    self.settings_open = !self.settings_open;
+                }
+                let stats_rect = gear_rect.translate(egui::vec2(-32.0, 0.0));
+                if ui.put(stats_rect, egui::Button::new("📊").frame(false)).clicked() {
+                    self.stats_open = !self.stats_open;
+                    if self.stats_open {
+                        self.refresh_stats();
+                    }
+                }
+                let long_break_rect = stats_rect.translate(egui::vec2(-32.0, 0.0));
+                if ui
+                    .put(long_break_rect, egui::Button::new("☕").frame(false))
+                    .on_hover_text("Take a long break now")
+                    .clicked()
+                {
+                    self.take_long_break(ctx);
+                }
+                
                 ui.vertical_centered(|ui| {
                     ui.add_space(40.0);
                     
-                    // Display session dots
-                    if self.today_session_count > 0 {
-                        let dots = "• ".repeat(self.today_session_count);
+                    // Data written by a newer version may not be handled correctly
+                    if let Some(warning) = self.db.schema_warning() {
+                        ui.label(egui::RichText::new(warning).size(12.0).color(theme.accent));
+                        ui.add_space(10.0);
+                    }
+                    
+                    // Cycle dots: filled for sessions done toward the next long break
+                    if !self.settings.continuous_mode {
+                        progress_dots(ui, self.cycle_position.min(SESSIONS_PER_CYCLE), SESSIONS_PER_CYCLE, theme.accent)
+                            .on_hover_text(format!(
+                                "{} of {} sessions until a long break",
+                                self.cycle_position.min(SESSIONS_PER_CYCLE),
+                                SESSIONS_PER_CYCLE
+                            ));
+                        ui.add_space(4.0);
+                    }
+                    
+                    // Total for the day, which is what the goal counts
+                    self.session_goal_progress(ui, theme);
+                    if let Some(committed) = self.todays_commitment() {
                         ui.label(
-                            egui::RichText::new(dots.trim_end())
-                                .size(20.0)
-                                .color(COLOR_ACCENT)
+                            egui::RichText::new(format!("{} of {} committed today", self.today_session_count, committed))
+                                .size(13.0)
+                                .color(theme.background)
                         );
-                        ui.add_space(10.0);
+                    } else if self.today_session_count > 0 && self.settings.daily_goal_sessions == 0 {
+                        ui.label(
+                            egui::RichText::new(format!("{} today", self.today_session_count))
+                                .size(13.0)
+                                .color(theme.background)
+                        );
+                    }
+                    ui.add_space(10.0);
+                    
+                    if self.welcome_back {
+                        ui.label(
+                            egui::RichText::new(&self.settings.welcome_back_message)
+                                .size(15.0)
+                                .color(theme.accent)
+                        );
+                        ui.add_space(6.0);
                     }
                     
                     ui.label(
                         egui::RichText::new("Pomodoro Timer")
                             .size(24.0)
-                            .color(COLOR_BACKGROUND)
+                            .color(theme.background)
                             .strong()
                     );
-                    ui.add_space(20.0);
+                    ui.add_space(10.0);
                     
-                    // Display timer
-                    ui.label(
-                        egui::RichText::new(self.format_time())
-                            .size(64.0)
-                            .monospace()
-                            .color(COLOR_BACKGROUND)
+                    // Task label, saved with the session when it completes
+                    autocomplete_edit(
+                        ui,
+                        "task_label",
+                        &mut self.current_label,
+                        &self.known_labels,
+                        "What are you working on?",
                     );
+                    let mut tag = self.current_tag.take().unwrap_or_default();
+                    ui.add(
+                        egui::TextEdit::singleline(&mut tag)
+                            .hint_text("Project tag")
+                            .desired_width(ui.available_width().min(240.0)),
+                    );
+                    self.current_tag = (!tag.is_empty()).then_some(tag);
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.current_note)
+                            .hint_text("Notes")
+                            .desired_rows(1)
+                            .desired_width(ui.available_width().min(240.0)),
+                    );
+                    ui.add_space(10.0);
+                    
+                    if let Some((_, starts_at)) = self.pending_break {
+                        self.break_grace_countdown(ui, ctx, starts_at, theme);
+                        return;
+                    }
+                    if self.goal_screen_showing() {
+                        self.goal_reached_screen(ui, theme);
+                        return;
+                    }
+                    
+                    // Display timer, scaled to the window
+                    let timer = ui.label(self.timer_text(work_timer_size(ctx.screen_rect().size()), theme)).rect;
+                    self.paint_progress_ring(ui, ring_slot, timer, theme);
+                    
+                    if self.idle_paused {
+                        ui.label(
+                            egui::RichText::new("Paused due to inactivity")
+                                .size(13.0)
+                                .color(theme.accent)
+                        );
+                    }
+                    
+                    let interruptions = self.session_interruptions();
+                    if interruptions > 0 {
+                        ui.label(
+                            egui::RichText::new(format!("Interruptions this session: {}", interruptions))
+                                .size(13.0)
+                                .color(theme.background)
+                        );
+                    }
                     
                     ui.add_space(30.0);
                     
+                    if self.interruption_note.is_some() {
+                        self.interruption_prompt(ui, ctx, theme);
+                        return;
+                    }
+                    if self.state.is_running() && !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::I)) {
+                        self.interrupt();
+                    }
+                    self.work_shortcuts(ctx);
+                    
                     // Control buttons (centered)
-                    ui.horizontal(|ui| {
-                        let button_width = 100.0;
-                        let num_buttons = if self.state != TimerState::Stopped { 2.0 } else { 1.0 };
-                        let spacing = ui.spacing().item_spacing.x;
-                        let total_width = button_width * num_buttons + spacing * (num_buttons - 1.0);
-                        let available_width = ui.available_width();
-                        ui.add_space((available_width - total_width) / 2.0);
-                        
-                        match self.state {
-                            TimerState::Stopped => {
-                                if ui.add_sized([button_width, 36.0], egui::Button::new(
-                                    egui::RichText::new("Start").size(18.0)
-                                )).clicked() {
-                                    self.start(ctx);
-                                }
-                            }
-                            TimerState::Running => {
-                                if ui.add_sized([button_width, 36.0], egui::Button::new(
-                                    egui::RichText::new("Pause").size(18.0)
-                                )).clicked() {
-                                    self.pause();
-                                }
-                            }
-                            TimerState::Paused => {
-                                if ui.add_sized([button_width, 36.0], egui::Button::new(
-                                    egui::RichText::new("Resume").size(18.0)
-                                )).clicked() {
-                                    self.start(ctx);
-                                }
-                            }
+                    let actions: &[ControlAction] = match self.state {
+                        TimerState::Stopped if self.break_ready => &[ControlAction::TakeBreak, ControlAction::Start],
+                        TimerState::Stopped => &[ControlAction::Start],
+                        // Continuous mode has no break to skip ahead to
+                        TimerState::Running { .. } if self.settings.continuous_mode => {
+                            &[ControlAction::Pause, ControlAction::Interrupt, ControlAction::Restart, ControlAction::Stop]
                         }
-                        
-                        if self.state != TimerState::Stopped {
-                            if ui.add_sized([button_width, 36.0], egui::Button::new(
-                                egui::RichText::new("Restart").size(18.0)
-                            )).clicked() {
-                                self.restart();
-                            }
+                        TimerState::Running { .. } => &[
+                            ControlAction::Pause,
+                            ControlAction::EndEarly,
+                            ControlAction::Interrupt,
+                            ControlAction::Restart,
+                            ControlAction::Stop,
+                        ],
+                        TimerState::Paused => {
+                            &[ControlAction::Resume, ControlAction::Restart, ControlAction::Stop]
                         }
-                    });
+                    };
+                    if let Some(action) = control_buttons(ui, 100.0, actions) {
+                        self.perform(action, ctx);
+                    }
                 });
             });
         } else {
+            // Reminder that a minimized break is still counting down
+            if self.break_window_minimized && self.remaining_seconds > 0 {
+                let hint = if self.settings.strict_mode { "strict mode" } else { "Enter to skip" };
+                egui::TopBottomPanel::top("break_banner")
+                    .frame(egui::Frame::none().fill(theme.secondary_dark).inner_margin(4.0))
+                    .show(ctx, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label(
+                                egui::RichText::new(if self.state == TimerState::Paused {
+                                    format!("Break paused: {} — {}", self.format_time(), hint)
+                                } else {
+                                    format!("Break: {} — {}", self.format_time(), hint)
+                                })
+                                    .size(13.0)
+                                    .color(theme.background)
+                            );
+                        });
+                    });
+            }
+            
             // Break period UI
             egui::CentralPanel::default().show(ctx, |ui| {
+                ui.multiply_opacity(content_opacity);
+                let ring_slot = ui.painter().add(egui::Shape::Noop);
+                
                 // Check for keyboard shortcuts during break
                 if self.remaining_seconds > 0 {
                     // Enter key to skip break
-                    if ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
-                        self.skip_break(ctx);
-                    }
-                    // ESC key to minimize fullscreen break window
-                    if ctx.input(|i| i.key_pressed(egui::Key::Escape)) && !self.break_window_minimized {
-                        self.minimize_break_window(ctx);
+                    if !self.settings.strict_mode && ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        self.request_skip(ctx);
                     }
                 }
                 
@@ -437,83 +1677,343 @@ impl eframe::App for PomodoroApp {
                     let title_size = if self.break_window_minimized { 24.0 } else { 32.0 };
                     let hint_size = if self.break_window_minimized { 14.0 } else { 16.0 };
                     
+                    let break_title = if self.state == TimerState::Paused {
+                        "Break Paused"
+                    } else if self.mode == PomodoroMode::LongBreak {
+                        "Long Break!"
+                    } else {
+                        "Break Time!"
+                    };
                     ui.label(
-                        egui::RichText::new("Break Time!")
+                        egui::RichText::new(break_title)
                             .size(title_size)
-                            .color(COLOR_BACKGROUND)
+                            .color(theme.background)
                             .strong()
                     );
-                    ui.add_space(20.0);
+                    ui.add_space(10.0);
+                    
+                    // Something to do with the break while it runs
+                    if self.remaining_seconds > 0 {
+                        ui.label(
+                            egui::RichText::new(&self.current_suggestion)
+                                .size(hint_size + 2.0)
+                                .color(theme.accent)
+                        );
+                    }
+                    ui.add_space(10.0);
                     
                     // Display break timer - smaller when minimized
-                    let timer_size = if self.break_window_minimized {
-                        64.0
-                    } else if self.remaining_seconds > 0 {
+                    let timer_size = if !self.break_window_minimized && self.remaining_seconds > 0 {
                         96.0
                     } else {
                         64.0
                     };
-                    ui.label(
-                        egui::RichText::new(self.format_time())
-                            .size(timer_size)
-                            .monospace()
-                            .color(COLOR_BACKGROUND)
-                    );
+                    let timer = ui.label(self.timer_text(timer_size, theme)).rect;
+                    self.paint_progress_ring(ui, ring_slot, timer, theme);
                     
                     ui.add_space(30.0);
                     
                     // Show keyboard hints during active break
                     if self.remaining_seconds > 0 {
+                        let skip_hint = if self.settings.strict_mode {
+                            "Strict mode is on: enjoy the full break"
+                        } else {
+                            "Press Enter to stay in the pocket and keep your flow"
+                        };
                         ui.label(
-                            egui::RichText::new("Press Enter to stay in the pocket and keep your flow")
+                            egui::RichText::new(skip_hint)
                                 .size(hint_size)
-                                .color(COLOR_BACKGROUND)
+                                .color(theme.background)
                         );
                         ui.add_space(10.0);
                         if !self.break_window_minimized {
                             ui.label(
                                 egui::RichText::new("Press ESC to minimize and multitask during break")
                                     .size(hint_size)
-                                    .color(COLOR_BACKGROUND)
+                                    .color(theme.background)
                             );
                         }
                         ui.add_space(20.0);
                     }
                     
+                    if self.skip_reason_prompt && self.remaining_seconds > 0 {
+                        self.skip_reason_buttons(ui, ctx, theme);
+                        return;
+                    }
+                    
                     // Break control buttons (centered)
-                    ui.horizontal(|ui| {
-                        let button_width = 120.0;
-                        let num_buttons = if self.remaining_seconds == 0 { 1.0 } else if self.break_window_minimized { 1.0 } else { 2.0 };
-                        let spacing = ui.spacing().item_spacing.x;
-                        let total_width = button_width * num_buttons + spacing * (num_buttons - 1.0);
-                        let available_width = ui.available_width();
-                        ui.add_space((available_width - total_width) / 2.0);
-                        
-                        if self.remaining_seconds == 0 {
-                            if ui.add_sized([button_width, 36.0], egui::Button::new(
-                                egui::RichText::new("Start New Timer").size(18.0)
-                            )).clicked() {
-                                self.start_work(ctx);
-                            }
+                    let mut actions = Vec::new();
+                    if self.remaining_seconds == 0 {
+                        actions.push(ControlAction::StartNewTimer);
+                    } else {
+                        // Pausing keeps the remaining break time and the fullscreen/minimized state
+                        actions.push(if self.state == TimerState::Paused {
+                            ControlAction::Resume
                         } else {
-                            if ui.add_sized([button_width, 36.0], egui::Button::new(
-                                egui::RichText::new("Skip Break").size(18.0)
-                            )).clicked() {
-                                self.skip_break(ctx);
-                            }
-                            
-                            // Only show Minimize button if not already minimized
-                            if !self.break_window_minimized {
-                                if ui.add_sized([button_width, 36.0], egui::Button::new(
-                                    egui::RichText::new("Minimize").size(18.0)
-                                )).clicked() {
-                                    self.minimize_break_window(ctx);
-                                }
-                            }
+                            ControlAction::Pause
+                        });
+                        if !self.settings.strict_mode {
+                            actions.push(ControlAction::SkipBreak);
                         }
-                    });
+                        // Only show Minimize button if not already minimized
+                        if !self.break_window_minimized {
+                            actions.push(ControlAction::Minimize);
+                        }
+                    }
+                    if let Some(action) = control_buttons(ui, 120.0, &actions) {
+                        self.perform(action, ctx);
+                    }
                 });
             });
         }
+        
+        self.check_contract_recap();
+        self.show_contract_prompt(ctx);
+        self.show_settings_window(ctx);
+        self.show_stats_window(ctx);
+        self.show_quit_prompt(ctx);
+    }
+}
+
+/// Work timer font size filling the room the rest of the work screen leaves,
+/// 64pt at the default 400x328 window
+fn work_timer_size(window: egui::Vec2) -> f32 {
+    // "00:00" is about three font sizes wide in the timer font
+    let by_width = window.x * 0.9 / 3.0;
+    let by_height = window.y - WORK_SCREEN_CHROME;
+    by_width.min(by_height).clamp(TIMER_FONT_RANGE.0, TIMER_FONT_RANGE.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::clock::MockClock;
+
+    /// An app on a scratch database file in its own folder, with nothing that would make a
+    /// sound or a notification. A file, so the session writer can open it too.
+    fn test_app(name: &str, clock: &Arc<MockClock>) -> PomodoroApp {
+        let db = Database::open_at(&scratch_dir(name).join("sessions.db"), clock.clone()).unwrap();
+        app_with(db, Settings::default(), clock)
+    }
+
+    fn app_with(db: Database, settings: Settings, clock: &Arc<MockClock>) -> PomodoroApp {
+        let settings = Settings {
+            sound_enabled: false,
+            notifications_enabled: false,
+            goal_notification: false,
+            ..settings
+        };
+        PomodoroApp::new(db, settings, SettingsBackend::Database, clock.clone())
+    }
+
+    /// An empty folder of its own for a test that needs real files
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("pocket_flow_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn test_clock() -> Arc<MockClock> {
+        Arc::new(MockClock::at(Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()))
+    }
+
+    #[test]
+    fn paused_time_does_not_count_down() {
+        let clock = test_clock();
+        let ctx = egui::Context::default();
+        let mut app = test_app("paused_time", &clock);
+        let work = app.work_duration;
+
+        app.start(&ctx);
+        clock.advance(Duration::from_secs(60));
+        app.update_timer(&ctx);
+        app.pause();
+        clock.advance(Duration::from_secs(600));
+        app.update_timer(&ctx);
+        assert_eq!(app.remaining_seconds, work - 60);
+
+        app.start(&ctx);
+        assert_eq!(app.state.remaining_at(clock.now_instant()), Some(work - 60));
+        clock.advance(Duration::from_secs(30));
+        app.update_timer(&ctx);
+        assert_eq!(app.remaining_seconds, work - 90);
+    }
+
+    #[test]
+    fn stalled_frames_still_count_the_time() {
+        let clock = test_clock();
+        let ctx = egui::Context::default();
+        let mut app = test_app("stalled_frames", &clock);
+        let work = app.work_duration;
+
+        app.start(&ctx);
+        clock.advance(Duration::from_secs(3));
+        app.update_timer(&ctx);
+        assert_eq!(app.remaining_seconds, work - 3);
+
+        // No frame runs for 10 seconds
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(app.state.remaining_at(clock.now_instant()), Some(work - 13));
+        app.update_timer(&ctx);
+        assert_eq!(app.remaining_seconds, work - 13);
+
+        // Part of a second left still shows as a whole one
+        clock.advance(Duration::from_millis(400));
+        assert_eq!(app.state.remaining_at(clock.now_instant()), Some(work - 13));
+    }
+
+    #[test]
+    fn completion_fires_once_however_many_frames_see_zero() {
+        let clock = test_clock();
+        let ctx = egui::Context::default();
+        let mut app = test_app("completion_once", &clock);
+
+        app.start(&ctx);
+        clock.advance(Duration::from_secs(app.work_duration));
+        for _ in 0..3 {
+            app.update_timer(&ctx);
+            clock.advance(Duration::from_secs(5));
+        }
+
+        assert_eq!(app.today_session_count, 1);
+        assert_eq!(app.cycle_position, 1);
+
+        // A period that has already completed stays done while frames keep seeing zero
+        app.mode = PomodoroMode::Work;
+        app.work_period = Some(WorkPeriod::start(clock.now_utc(), app.work_duration));
+        app.state = TimerState::running_for(0, clock.now_instant());
+        app.completion_fired = true;
+        for _ in 0..3 {
+            app.update_timer(&ctx);
+        }
+        assert_eq!(app.today_session_count, 1);
+        app.session_writer.flush();
+        assert_eq!(app.db.get_total_session_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn next_break_type_survives_a_restart() {
+        let clock = test_clock();
+        let ctx = egui::Context::default();
+        let dir = scratch_dir("cycle_restart");
+        let path = dir.join("sessions.db");
+        let settings = Settings {
+            auto_break: false,
+            ..Settings::default()
+        };
+
+        let mut completed = 0;
+        for (sessions, expected) in [(1, PomodoroMode::Break), (SESSIONS_PER_CYCLE, PomodoroMode::LongBreak)] {
+            let db = Database::open_at(&path, clock.clone()).unwrap();
+            let mut app = app_with(db, settings.clone(), &clock);
+            while completed < sessions {
+                app.start(&ctx);
+                clock.advance(Duration::from_secs(app.work_duration));
+                app.update_timer(&ctx);
+                completed += 1;
+            }
+            drop(app);
+
+            let db = Database::open_at(&path, clock.clone()).unwrap();
+            assert_eq!(db.get_cycle_position().unwrap(), sessions);
+            let mut app = app_with(db, settings.clone(), &clock);
+            app.start_break(&ctx);
+            assert!(app.mode == expected);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn held_back_session_reaches_the_goal_once_its_break_is_taken() {
+        let clock = test_clock();
+        let ctx = egui::Context::default();
+        let dir = scratch_dir("goal_after_break");
+        let settings = Settings {
+            daily_goal_sessions: 1,
+            goal_requires_break: true,
+            ..Settings::default()
+        };
+        let db = Database::open_at(&dir.join("sessions.db"), clock.clone()).unwrap();
+        let mut app = app_with(db, settings, &clock);
+
+        app.start(&ctx);
+        clock.advance(Duration::from_secs(app.work_duration));
+        app.update_timer(&ctx);
+        assert_eq!((app.today_session_count, app.today_goal_count), (1, 0));
+
+        // The break can only credit the session once the writer has saved it
+        for _ in 0..500 {
+            app.handle_saved_sessions();
+            if app.goal_pending_session.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(app.goal_pending_session.is_some());
+
+        clock.advance(Duration::from_secs(app.break_duration));
+        app.update_timer(&ctx);
+        assert_eq!((app.today_session_count, app.today_goal_count), (1, 1));
+        assert_eq!(app.db.get_today_goal_count().unwrap(), 1);
+        app.start_work(&ctx);
+        assert!(app.goal_screen_showing());
+
+        drop(app);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn completed_session_is_written_to_the_database() {
+        let clock = test_clock();
+        let ctx = egui::Context::default();
+        let mut app = test_app("session_written", &clock);
+        app.current_label = "Writing".to_string();
+        app.current_note = "Outline".to_string();
+        app.current_tag = Some(" Pocket Flow ".to_string());
+
+        app.start(&ctx);
+        clock.advance(Duration::from_secs(app.work_duration));
+        app.update_timer(&ctx);
+        app.session_writer.flush();
+        app.handle_saved_sessions();
+
+        let saved = app.db.get_recent_sessions(10, 0).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].duration_seconds, app.work_duration as i64);
+        assert_eq!(saved[0].end_reason, EndReason::Completed);
+        assert_eq!(saved[0].label.as_deref(), Some("Writing"));
+        assert_eq!(saved[0].note.as_deref(), Some("Outline"));
+        assert_eq!(saved[0].tag.as_deref(), Some("Pocket Flow"));
+        assert_eq!(app.db.get_today_totals().unwrap(), (1, app.work_duration as i64));
+        assert_eq!(app.known_labels, ["Writing"]);
+    }
+
+    #[test]
+    fn todays_count_starts_over_after_midnight() {
+        let evening = Local.with_ymd_and_hms(2024, 3, 1, 23, 20, 0).unwrap().with_timezone(&Utc);
+        let clock = Arc::new(MockClock::at(evening));
+        let ctx = egui::Context::default();
+        let mut app = test_app("midnight", &clock);
+
+        app.start(&ctx);
+        clock.advance(Duration::from_secs(app.work_duration));
+        app.update_timer(&ctx);
+        app.session_writer.flush();
+        assert_eq!(app.db.get_today_totals().unwrap().0, 1);
+
+        // 23:59:30, then just past midnight but inside the check interval
+        clock.advance(Duration::from_secs(14 * 60 + 30));
+        app.check_day_rollover();
+        clock.advance(Duration::from_secs(40));
+        app.check_day_rollover();
+        assert_eq!(app.today_session_count, 1);
+
+        clock.advance(DAY_CHECK_INTERVAL);
+        app.check_day_rollover();
+        assert_eq!(app.today_session_count, 0);
+        assert_eq!(app.counted_day, NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
     }
 }
@@ -0,0 +1,257 @@
+use std::time::Duration;
+use eframe::egui;
+use chrono::Local;
+
+use crate::models::{EndReason, WorkSession};
+use crate::PomodoroApp;
+
+// Completed sessions should last about one work period; anything past twice that is suspect
+const SUSPECT_FACTOR: i64 = 2;
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60); // Retention re-checked while running
+const DEFAULT_RETENTION_DAYS: u32 = 365;
+const RESET_CONFIRMATION: &str = "delete"; // Typed to confirm Reset all data
+
+#[derive(Clone, Copy)]
+enum Fix {
+    Clamp,
+    Delete,
+}
+
+impl PomodoroApp {
+    /// Settings section for finding and fixing sessions with implausible durations.
+    /// Nothing is changed until the user picks a fix for a listed row.
+    pub(crate) fn maintenance_section(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Check session durations").clicked() {
+                self.scan_suspect_sessions();
+            }
+        });
+
+        let Some(suspects) = &self.suspect_sessions else { return };
+        if suspects.is_empty() {
+            ui.label("No suspect sessions found");
+            return;
+        }
+
+        ui.label(format!("{} sessions with implausible durations:", suspects.len()));
+        let mut fixes: Vec<(WorkSession, Fix)> = Vec::new();
+
+        egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+            for session in suspects {
+                ui.horizontal(|ui| {
+                    let started = session.started_at.with_timezone(&Local);
+                    ui.label(format!(
+                        "{}  {}s",
+                        started.format("%b %d  %H:%M"),
+                        session.duration_seconds
+                    ));
+                    if ui.small_button("Clamp").on_hover_text("Set to one work period").clicked() {
+                        fixes.push((session.clone(), Fix::Clamp));
+                    }
+                    if ui.small_button("Delete").clicked() {
+                        fixes.push((session.clone(), Fix::Delete));
+                    }
+                });
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Clamp all").clicked() {
+                fixes = suspects.iter().map(|s| (s.clone(), Fix::Clamp)).collect();
+            }
+            if ui.button("Delete all").clicked() {
+                fixes = suspects.iter().map(|s| (s.clone(), Fix::Delete)).collect();
+            }
+        });
+
+        if !fixes.is_empty() {
+            for (session, fix) in &fixes {
+                self.apply_fix(session, *fix);
+            }
+            self.refresh_today_totals();
+            self.refresh_stats();
+            self.scan_suspect_sessions();
+        }
+    }
+
+    /// Settings control for deleting old history. Turning it on asks for confirmation first,
+    /// since deleted sessions can't be recovered.
+    pub(crate) fn retention_section(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        if let Some(days) = &mut self.retention_pending {
+            ui.label(
+                egui::RichText::new(format!(
+                    "Sessions older than {} days will be deleted permanently, now and from then on. \
+                     Consider backing up sessions.db from the data folder first.",
+                    days
+                ))
+                .color(ui.visuals().warn_fg_color),
+            );
+            let mut decided = None;
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(days).range(1..=3650).suffix(" days"));
+                if ui.button("Delete old sessions").clicked() {
+                    decided = Some(true);
+                }
+                if ui.button("Cancel").clicked() {
+                    decided = Some(false);
+                }
+            });
+            match decided {
+                Some(true) => {
+                    self.settings.retention_days = self.retention_pending.take().unwrap_or(0);
+                    self.prune_old_sessions();
+                    changed = true;
+                }
+                Some(false) => self.retention_pending = None,
+                None => {}
+            }
+            return changed;
+        }
+
+        ui.horizontal(|ui| {
+            let mut enabled = self.settings.retention_days > 0;
+            if ui.checkbox(&mut enabled, "Delete sessions older than").changed() {
+                if enabled {
+                    self.retention_pending = Some(DEFAULT_RETENTION_DAYS);
+                } else {
+                    self.settings.retention_days = 0;
+                    changed = true;
+                }
+            }
+            if enabled {
+                let response = ui.add(
+                    egui::DragValue::new(&mut self.settings.retention_days)
+                        .range(1..=3650)
+                        .suffix(" days"),
+                );
+                changed |= response.changed();
+                // Only prune once dragging stops, not at every intermediate value
+                if response.drag_stopped() || response.lost_focus() {
+                    self.prune_old_sessions();
+                }
+            }
+        });
+        changed
+    }
+
+    /// Re-apply retention every PRUNE_INTERVAL so a long-running app keeps trimming
+    pub(crate) fn prune_if_due(&mut self) {
+        let now = self.clock.now_instant();
+        if self.last_prune.is_some_and(|last| now.duration_since(last) < PRUNE_INTERVAL) {
+            return;
+        }
+        self.prune_old_sessions();
+    }
+
+    /// Delete sessions past Settings::retention_days, if set
+    pub(crate) fn prune_old_sessions(&mut self) {
+        self.last_prune = Some(self.clock.now_instant());
+        let days = self.settings.retention_days;
+        if days == 0 {
+            return;
+        }
+
+        let cutoff = self.db.current_day() - chrono::Duration::days(days as i64);
+        match self.db.prune_before(cutoff) {
+            Ok(0) => {}
+            Ok(removed) => {
+                eprintln!("Pruned {} sessions from before {}", removed, cutoff);
+                self.load_known_labels();
+                if self.stats_open {
+                    self.refresh_stats();
+                }
+            }
+            Err(e) => eprintln!("Failed to prune old sessions: {}", e),
+        }
+    }
+
+    /// Reset all data, which only goes through once RESET_CONFIRMATION has been typed
+    pub(crate) fn reset_section(&mut self, ui: &mut egui::Ui) {
+        let Some(typed) = &mut self.reset_confirmation else {
+            if ui.button("Reset all data…").on_hover_text("Delete every work and break session").clicked() {
+                self.reset_confirmation = Some(String::new());
+            }
+            return;
+        };
+
+        ui.label(
+            egui::RichText::new(format!(
+                "Every work and break session will be deleted permanently. Settings are kept. \
+                 Type \"{}\" to confirm.",
+                RESET_CONFIRMATION
+            ))
+            .color(ui.visuals().warn_fg_color),
+        );
+        let mut decided = None;
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(typed).hint_text(RESET_CONFIRMATION).desired_width(80.0));
+            let confirmed = typed.trim() == RESET_CONFIRMATION;
+            if ui.add_enabled(confirmed, egui::Button::new("Delete everything")).clicked() {
+                decided = Some(true);
+            }
+            if ui.button("Cancel").clicked() {
+                decided = Some(false);
+            }
+        });
+        match decided {
+            Some(true) => {
+                self.reset_confirmation = None;
+                self.clear_all_sessions();
+            }
+            Some(false) => self.reset_confirmation = None,
+            None => {}
+        }
+    }
+
+    fn clear_all_sessions(&mut self) {
+        if let Err(e) = self.db.clear_all_sessions() {
+            eprintln!("Failed to reset data: {}", e);
+            return;
+        }
+        eprintln!("Deleted all sessions");
+        self.goal_pending_session = None;
+        self.suspect_sessions = None;
+        self.delete_pending = None;
+        self.history.clear();
+        self.set_cycle_position(0);
+        self.refresh_today_totals();
+        self.load_known_labels();
+        self.refresh_streaks();
+        if self.stats_open {
+            self.refresh_stats();
+        }
+    }
+
+    fn scan_suspect_sessions(&mut self) {
+        match self.db.get_suspect_sessions(self.work_duration as i64 * SUSPECT_FACTOR) {
+            Ok(sessions) => self.suspect_sessions = Some(sessions),
+            Err(e) => eprintln!("Failed to check session durations: {}", e),
+        }
+    }
+
+    fn apply_fix(&self, session: &WorkSession, fix: Fix) {
+        let Some(id) = session.id else { return };
+        let result = match fix {
+            // A duration this far off almost always comes from a clock change mid-session
+            Fix::Clamp => self
+                .db
+                .set_session_duration(id, self.work_duration as i64)
+                .and_then(|()| self.db.set_end_reason(id, EndReason::ClockJump)),
+            Fix::Delete => self.db.delete_session(id),
+        };
+
+        match (result, fix) {
+            (Ok(()), Fix::Clamp) => eprintln!(
+                "Clamped session {} ({}) from {}s to {}s",
+                id, session.started_at, session.duration_seconds, self.work_duration
+            ),
+            (Ok(()), Fix::Delete) => eprintln!(
+                "Deleted session {} ({}) with duration {}s",
+                id, session.started_at, session.duration_seconds
+            ),
+            (Err(e), _) => eprintln!("Failed to fix session {}: {}", id, e),
+        }
+    }
+}
@@ -1,19 +1,107 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Why a session record exists, stored as text in work_sessions.end_reason
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum EndReason {
+    #[default]
+    Completed,   // Ran its full length
+    EndedEarly,  // Ended by the user before the timer ran out
+    Interrupted, // Cut off by the app quitting and resumed on the next launch
+    ClockJump,   // Wall-clock duration disagrees with the timer, e.g. the clock was changed
+}
+
+impl EndReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndReason::Completed => "completed",
+            EndReason::EndedEarly => "ended_early",
+            EndReason::Interrupted => "interrupted",
+            EndReason::ClockJump => "clock_jump",
+        }
+    }
+
+    /// Unknown values (e.g. from a newer app) read as Completed
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "ended_early" => EndReason::EndedEarly,
+            "interrupted" => EndReason::Interrupted,
+            "clock_jump" => EndReason::ClockJump,
+            _ => EndReason::Completed,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EndReason::Completed => "Completed",
+            EndReason::EndedEarly => "Ended early",
+            EndReason::Interrupted => "Interrupted",
+            EndReason::ClockJump => "Clock changed",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct WorkSession {
+    pub id: Option<i64>, // None until saved
     pub started_at: DateTime<Utc>,
     pub completed_at: DateTime<Utc>,
     pub duration_seconds: i64,
+    pub note: Option<String>,
+    pub label: Option<String>, // Task the session was spent on
+    pub counts_toward_goal: bool, // False while waiting for the following break, see Settings::goal_requires_break
+    pub end_reason: EndReason,
+    pub paused_seconds: i64, // Time spent paused, not included in duration_seconds
+    pub interruptions: u32, // Recorded with the Interrupt button, see interruptions.rs
+    pub pause_count: u32,   // Times the session was paused, by hand or when idle
+    pub tag: Option<String>, // Project the session belongs to
 }
 
 impl WorkSession {
     pub fn new(started_at: DateTime<Utc>, completed_at: DateTime<Utc>) -> Self {
         let duration_seconds = (completed_at - started_at).num_seconds();
         Self {
+            id: None,
             started_at,
             completed_at,
             duration_seconds,
+            note: None,
+            label: None,
+            counts_toward_goal: true,
+            end_reason: EndReason::Completed,
+            paused_seconds: 0,
+            interruptions: 0,
+            pause_count: 0,
+            tag: None,
         }
     }
 }
+
+/// One break that ran out or was skipped
+#[derive(Debug, Clone)]
+pub struct BreakSession {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_seconds: i64, // Time on the break timer; short of the planned length when skipped
+    pub long_break: bool,
+    pub skipped: bool,
+    pub skip_reason: Option<String>, // Only when asked, see Settings::ask_skip_reason
+}
+
+/// Sessions the user committed to for one day, see contract.rs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusContract {
+    pub day: NaiveDate,
+    pub sessions: u32, // 0 = declined for the day
+    pub recapped: bool, // End-of-day recap already shown
+}
+
+/// Per-day rollup kept in the daily_summaries table, so long ranges don't rescan sessions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailySummary {
+    pub day: NaiveDate,
+    pub sessions: usize,
+    pub focus_seconds: i64,
+    pub goal_seconds: i64,   // Focus time that counts toward the daily goal
+    pub paused_seconds: i64,
+    pub interruptions: usize, // Summed over the day's sessions
+}
@@ -0,0 +1,19 @@
+use notify_rust::Notification;
+
+/// Show a desktop notification on a detached thread so a slow backend never blocks the UI.
+/// Failures (no notification daemon, denied permission) are logged and otherwise ignored.
+pub fn send(summary: &str, body: &str) {
+    let summary = summary.to_string();
+    let body = body.to_string();
+
+    std::thread::spawn(move || {
+        if let Err(e) = Notification::new()
+            .appname("Pocket Flow")
+            .summary(&summary)
+            .body(&body)
+            .show()
+        {
+            eprintln!("Failed to show notification: {}", e);
+        }
+    });
+}
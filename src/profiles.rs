@@ -0,0 +1,94 @@
+use eframe::egui;
+
+use crate::autocomplete::autocomplete_edit;
+use crate::config::Profile;
+use crate::theme::PRESETS;
+use crate::PomodoroApp;
+
+enum Edit {
+    PickSound(usize),
+    ResetSound(usize),
+    Remove(usize),
+}
+
+impl PomodoroApp {
+    /// Settings section listing the per-task profiles. Returns whether anything changed.
+    pub(crate) fn profiles_section(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let mut edit = None;
+
+        egui::CollapsingHeader::new("Task profiles").show(ui, |ui| {
+            ui.label(
+                egui::RichText::new("Theme and sound used while the task label matches").small(),
+            );
+            for (i, profile) in self.settings.profiles.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    let response = autocomplete_edit(
+                        ui,
+                        &format!("profile_label_{}", i),
+                        &mut profile.label,
+                        &self.known_labels,
+                        "Task label",
+                    );
+                    changed |= response.lost_focus();
+
+                    let theme_name = if profile.theme.is_empty() { "Default theme" } else { profile.theme.as_str() };
+                    egui::ComboBox::from_id_salt(("profile_theme", i))
+                        .selected_text(theme_name)
+                        .show_ui(ui, |ui| {
+                            changed |= ui
+                                .selectable_value(&mut profile.theme, String::new(), "Default theme")
+                                .changed();
+                            for theme in PRESETS {
+                                changed |= ui
+                                    .selectable_value(&mut profile.theme, theme.name.to_string(), theme.name)
+                                    .changed();
+                            }
+                        });
+
+                    let sound = std::path::Path::new(profile.sound_file.trim())
+                        .file_name()
+                        .map_or_else(|| "Default sound".to_string(), |n| n.to_string_lossy().into_owned());
+                    if ui.small_button(sound).on_hover_text("Choose a completion sound").clicked() {
+                        edit = Some(Edit::PickSound(i));
+                    }
+                    if !profile.sound_file.is_empty() && ui.small_button("Reset").clicked() {
+                        edit = Some(Edit::ResetSound(i));
+                    }
+                    if ui.small_button("🗑").on_hover_text("Remove profile").clicked() {
+                        edit = Some(Edit::Remove(i));
+                    }
+                });
+            }
+            if ui.button("Add profile").clicked() {
+                self.settings.profiles.push(Profile {
+                    label: self.current_label.trim().to_string(),
+                    ..Profile::default()
+                });
+                changed = true;
+            }
+        });
+
+        match edit {
+            Some(Edit::PickSound(i)) => {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Audio", &["wav", "mp3", "ogg", "flac"])
+                    .pick_file()
+                {
+                    self.settings.profiles[i].sound_file = path.to_string_lossy().into_owned();
+                    changed = true;
+                }
+            }
+            Some(Edit::ResetSound(i)) => {
+                self.settings.profiles[i].sound_file.clear();
+                changed = true;
+            }
+            Some(Edit::Remove(i)) => {
+                self.settings.profiles.remove(i);
+                changed = true;
+            }
+            None => {}
+        }
+        changed
+    }
+}
@@ -0,0 +1,51 @@
+use std::f32::consts::TAU;
+
+use eframe::egui;
+
+use crate::theme::Theme;
+use crate::PomodoroApp;
+
+const RING_PADDING: f32 = 14.0; // Gap between the countdown text and the ring
+const RING_WIDTH: f32 = 6.0;
+const RING_SEGMENTS: usize = 120; // Points on a full circle
+
+impl PomodoroApp {
+    /// Share of the current period still to go, 1.0 at the start and 0.0 once it's over.
+    /// Clamped in case the duration was shortened while the period ran.
+    pub(crate) fn remaining_fraction(&self) -> f32 {
+        let full = self.duration_of(self.mode).max(1);
+        (self.remaining_seconds as f32 / full as f32).clamp(0.0, 1.0)
+    }
+
+    /// Fill a slot reserved before the screen was laid out with a ring around the countdown,
+    /// so it sits behind everything else on the panel. The arc runs clockwise from the top and
+    /// shrinks as the period runs down.
+    pub(crate) fn paint_progress_ring(&self, ui: &egui::Ui, slot: egui::layers::ShapeIdx, timer: egui::Rect, theme: &Theme) {
+        let color = if self.mode.is_break() { theme.secondary } else { theme.accent };
+        let bounds = ui.clip_rect();
+        let radius = (timer.width() / 2.0 + RING_PADDING)
+            .min(bounds.width() / 2.0 - RING_WIDTH)
+            .min(bounds.height() / 2.0 - RING_WIDTH);
+        if radius <= 0.0 {
+            return;
+        }
+        let center = timer.center();
+
+        let track = egui::Shape::circle_stroke(center, radius, egui::Stroke::new(RING_WIDTH, color.gamma_multiply(0.2)));
+        let fraction = self.remaining_fraction();
+        let shape = if fraction > 0.0 {
+            let steps = ((RING_SEGMENTS as f32 * fraction).ceil() as usize).max(1);
+            let points = (0..=steps)
+                .map(|i| {
+                    let angle = TAU * fraction * i as f32 / steps as f32 - TAU / 4.0;
+                    center + radius * egui::vec2(angle.cos(), angle.sin())
+                })
+                .collect();
+            let arc = egui::Shape::line(points, egui::Stroke::new(RING_WIDTH, color));
+            egui::Shape::Vec(vec![track, arc])
+        } else {
+            track
+        };
+        ui.painter().set(slot, shape);
+    }
+}
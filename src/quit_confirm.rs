@@ -0,0 +1,56 @@
+use eframe::egui;
+
+use crate::{PomodoroApp, PomodoroMode, TimerState};
+
+impl PomodoroApp {
+    /// Hold a close request, from the window or the tray's Quit, while a work session is
+    /// running or paused, and ask first. Anything else closes right away.
+    pub(crate) fn check_close_request(&mut self, ctx: &egui::Context) {
+        if !ctx.input(|i| i.viewport().close_requested()) || self.quit_confirmed {
+            return;
+        }
+        if self.mode != PomodoroMode::Work || self.state == TimerState::Stopped {
+            return;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        self.quit_prompt = true;
+        self.show_window(ctx);
+    }
+
+    pub(crate) fn show_quit_prompt(&mut self, ctx: &egui::Context) {
+        if !self.quit_prompt {
+            return;
+        }
+        // Nothing left to lose once the session has ended
+        if self.mode != PomodoroMode::Work || self.state == TimerState::Stopped {
+            self.quit_prompt = false;
+            return;
+        }
+        let mut decided = None;
+        egui::Window::new("Quit during a session?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("This session has {} left.", self.format_time()));
+                ui.label("It won't be saved now; it's kept paused for the next launch.");
+                ui.horizontal(|ui| {
+                    if ui.button("Quit").clicked() {
+                        decided = Some(true);
+                    }
+                    if ui.button("Keep Working").clicked() {
+                        decided = Some(false);
+                    }
+                });
+            });
+
+        match decided {
+            Some(true) => {
+                self.quit_confirmed = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            Some(false) => self.quit_prompt = false,
+            None => {}
+        }
+    }
+}
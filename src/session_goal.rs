@@ -0,0 +1,98 @@
+use eframe::egui;
+
+use crate::theme::Theme;
+use crate::{notifications, PomodoroApp, PomodoroMode, TimerState};
+
+impl PomodoroApp {
+    /// Today's sessions against Settings::daily_goal_sessions, as a row of dots and "3 / 8",
+    /// or a celebration once the goal is met
+    pub(crate) fn session_goal_progress(&self, ui: &mut egui::Ui, theme: &Theme) {
+        let goal = self.settings.daily_goal_sessions as usize;
+        if goal == 0 {
+            return;
+        }
+        let done = self.today_goal_count;
+
+        progress_dots(ui, done.min(goal), goal, theme.accent)
+            .on_hover_text(format!("{} of {} sessions in today's goal", done.min(goal), goal));
+        ui.add_space(2.0);
+        let text = if done >= goal {
+            format!("Goal reached! {} / {} today 🎉", done, goal)
+        } else {
+            format!("{} / {} today", done, goal)
+        };
+        let color = if done >= goal { theme.accent } else { theme.background };
+        ui.label(egui::RichText::new(text).size(13.0).color(color));
+    }
+
+    /// Whether the work screen shows the done-for-today summary in place of the timer: the
+    /// goal is met, nothing is running or waiting on a break, and Keep going hasn't been
+    /// pressed today
+    pub(crate) fn goal_screen_showing(&self) -> bool {
+        let goal = self.settings.daily_goal_sessions as usize;
+        goal > 0
+            && self.today_goal_count >= goal
+            && self.mode == PomodoroMode::Work
+            && self.state == TimerState::Stopped
+            && !self.break_ready
+            && self.goal_screen_dismissed != Some(self.counted_day)
+    }
+
+    pub(crate) fn goal_reached_screen(&mut self, ui: &mut egui::Ui, theme: &Theme) {
+        let locale = self.settings.number_locale;
+        ui.label(egui::RichText::new("Done for today 🎉").size(32.0).color(theme.accent));
+        ui.add_space(8.0);
+        let pomodoros = match self.today_session_count {
+            1 => "1 pomodoro".to_string(),
+            count => format!("{} pomodoros", locale.integer(count as i64)),
+        };
+        ui.label(
+            egui::RichText::new(format!("{} · {} focused", pomodoros, locale.hours_minutes(self.today_focus_seconds)))
+                .size(16.0)
+                .color(theme.background),
+        );
+        let streak = match self.streaks.0 {
+            1 => "1 day streak".to_string(),
+            days => format!("{} day streak", locale.integer(days as i64)),
+        };
+        ui.label(egui::RichText::new(streak).size(14.0).color(theme.background));
+        ui.add_space(20.0);
+        if ui.button(egui::RichText::new("Keep going").size(18.0)).clicked() {
+            self.goal_screen_dismissed = Some(self.counted_day);
+        }
+    }
+
+    /// Called each time a session starts counting toward the goal, when it's saved or once
+    /// its break is taken, so the notification fires once, on the one that reaches the goal
+    pub(crate) fn check_session_goal(&self) {
+        let goal = self.settings.daily_goal_sessions as usize;
+        if goal == 0 || self.today_goal_count != goal || !self.settings.goal_notification {
+            return;
+        }
+        notifications::send(
+            "Daily goal reached",
+            &format!("{} pomodoros done today. Nice work!", goal),
+        );
+    }
+}
+
+/// Dots filled up to `filled` of `total`, packed closer together when the row would be wider
+/// than the space available
+pub fn progress_dots(ui: &mut egui::Ui, filled: usize, total: usize, color: egui::Color32) -> egui::Response {
+    let radius = 5.0;
+    let count = total as f32;
+    let gap = ((ui.available_width() - count * radius * 2.0) / (count - 1.0).max(1.0)).clamp(2.0, 8.0);
+    let size = egui::vec2(count * radius * 2.0 + (count - 1.0) * gap, radius * 2.0);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+
+    for i in 0..total {
+        let center = egui::pos2(rect.left() + radius + i as f32 * (radius * 2.0 + gap), rect.center().y);
+        if i < filled {
+            ui.painter().circle_filled(center, radius, color);
+        } else {
+            ui.painter().circle_stroke(center, radius - 0.5, egui::Stroke::new(1.0, color));
+        }
+    }
+
+    response
+}
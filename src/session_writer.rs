@@ -0,0 +1,85 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+
+use crate::db::Database;
+use crate::models::WorkSession;
+
+/// A session handed to the writer, and how saving it went
+pub struct WriteOutcome {
+    pub session: WorkSession, // With its id set if it was saved
+    pub error: Option<String>,
+}
+
+/// Saves work sessions on a background thread with its own connection, so disk I/O never
+/// stalls a frame. Sessions are written in the order they're sent.
+pub struct SessionWriter {
+    sender: Option<Sender<WorkSession>>, // Dropped once flushed, which ends the thread
+    outcomes: Receiver<WriteOutcome>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SessionWriter {
+    /// Falls back to a writer whose saves all fail (and are logged as such) if the second
+    /// connection can't be opened
+    pub fn start(db: &Database) -> Self {
+        let (sender, requests) = mpsc::channel::<WorkSession>();
+        let (reply, outcomes) = mpsc::channel();
+
+        let writer = match db.reopen() {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                eprintln!("Failed to open database for the session writer: {}", e);
+                None
+            }
+        };
+        let thread = std::thread::Builder::new()
+            .name("session-writer".to_string())
+            .spawn(move || {
+                for mut session in requests {
+                    let result = match &writer {
+                        Some(writer) => writer.insert_work_session(&session).map_err(|e| e.to_string()),
+                        None => Err("no database connection".to_string()),
+                    };
+                    let error = match result {
+                        Ok(id) => {
+                            session.id = Some(id);
+                            None
+                        }
+                        Err(e) => Some(e),
+                    };
+                    // The app may already be gone when the last write finishes
+                    let _ = reply.send(WriteOutcome { session, error });
+                }
+            })
+            .map_err(|e| eprintln!("Failed to start session writer: {}", e))
+            .ok();
+
+        Self {
+            sender: Some(sender),
+            outcomes,
+            thread,
+        }
+    }
+
+    pub fn save(&self, session: WorkSession) {
+        let sent = self.sender.as_ref().is_some_and(|sender| sender.send(session).is_ok());
+        if !sent {
+            eprintln!("Failed to save work session: session writer isn't running");
+        }
+    }
+
+    /// Outcomes of saves finished since the last call
+    pub fn finished(&self) -> Vec<WriteOutcome> {
+        self.outcomes.try_iter().collect()
+    }
+
+    /// Wait for every session sent so far to be written. Nothing can be saved afterwards.
+    pub fn flush(&mut self) {
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                eprintln!("Session writer stopped unexpectedly");
+            }
+        }
+    }
+}
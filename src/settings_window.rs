@@ -0,0 +1,694 @@
+use std::path::Path;
+
+use eframe::egui;
+use chrono::Weekday;
+
+use crate::audio;
+use crate::config::{AmbientSound, BreakEndAction, DayBoundary, DisplayChangeAction, EscapeAction, ThemeMode, DURATION_MINUTES, MIN_WINDOW_FLOOR};
+use crate::idle;
+use crate::import::{import_csv, ImportFormat};
+use crate::locale::NumberLocale;
+#[cfg(unix)]
+use crate::state_socket::StateSocket;
+use crate::theme::{Theme, PRESETS};
+use crate::PomodoroApp;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+impl PomodoroApp {
+    pub(crate) fn show_settings_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.settings_open;
+        let mut changed = false;
+        let mut day_rules_changed = false;
+        let mut import_clicked = false;
+        let mut export_clicked = false;
+        let mut pick_sound = false;
+        let mut pick_ambient = false;
+        let mut socket_changed = false;
+        let mut durations_changed = false;
+
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                // Too many rows for one screen, and the window keeps its size
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Work");
+                        durations_changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.settings.work_minutes)
+                                    .range(DURATION_MINUTES)
+                                    .suffix(" min"),
+                            )
+                            .changed();
+                        ui.label("Break");
+                        durations_changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.settings.break_minutes)
+                                    .range(DURATION_MINUTES)
+                                    .suffix(" min"),
+                            )
+                            .changed();
+                        ui.label("Long break");
+                        durations_changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.settings.long_break_minutes)
+                                    .range(DURATION_MINUTES)
+                                    .suffix(" min"),
+                            )
+                            .on_hover_text("After every fourth work session")
+                            .changed();
+                    });
+
+                    // Theme preset picker
+                    ui.horizontal(|ui| {
+                        ui.label("Theme");
+                        egui::ComboBox::from_id_salt("theme_preset")
+                            .selected_text(Theme::by_name(&self.settings.theme).name)
+                            .show_ui(ui, |ui| {
+                                for theme in PRESETS {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut self.settings.theme,
+                                            theme.name.to_string(),
+                                            theme.name,
+                                        )
+                                        .changed();
+                                }
+                            });
+                        for mode in ThemeMode::ALL {
+                            changed |= ui
+                                .selectable_value(&mut self.settings.theme_mode, mode, mode.label())
+                                .changed();
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Opacity");
+                        changed |= ui
+                            .add(egui::Slider::new(&mut self.settings.window_opacity, 0.3..=1.0))
+                            .changed();
+                    });
+                    if self.settings.wants_transparent_window() && !self.transparent_window {
+                        ui.label(egui::RichText::new("Restart to make the window transparent").small());
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Smallest window");
+                        let mut size_changed = false;
+                        for value in [&mut self.settings.min_window_width, &mut self.settings.min_window_height] {
+                            size_changed |= ui
+                                .add(egui::DragValue::new(value).range(MIN_WINDOW_FLOOR..=2000.0).suffix(" pt"))
+                                .changed();
+                        }
+                        if size_changed {
+                            ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(
+                                self.settings.min_window_size().into(),
+                            ));
+                            changed = true;
+                        }
+                    })
+                    .response
+                    .on_hover_text("Width and height the window can't be resized below");
+                    ui.horizontal(|ui| {
+                        ui.label("Number format");
+                        egui::ComboBox::from_id_salt("number_locale")
+                            .selected_text(self.settings.number_locale.label())
+                            .show_ui(ui, |ui| {
+                                for locale in NumberLocale::ALL {
+                                    changed |= ui
+                                        .selectable_value(&mut self.settings.number_locale, locale, locale.label())
+                                        .changed();
+                                }
+                            });
+                    });
+                    changed |= ui
+                        .checkbox(&mut self.settings.reduce_motion, "Reduce motion")
+                        .on_hover_text("Switch between work and break screens without fading")
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut self.settings.final_minute_seconds, "Seconds only in the last minute")
+                        .on_hover_text("Show \"45\" instead of \"00:45\", larger and highlighted")
+                        .changed();
+
+                    ui.separator();
+
+                    // Day bucketing rules
+                    ui.horizontal(|ui| {
+                        ui.label("Day starts at");
+                        day_rules_changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.settings.day_start_hour)
+                                    .range(0..=23)
+                                    .suffix(":00"),
+                            )
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Count sessions by");
+                        day_rules_changed |= ui
+                            .radio_value(&mut self.settings.day_boundary, DayBoundary::StartTime, "start")
+                            .changed();
+                        day_rules_changed |= ui
+                            .radio_value(
+                                &mut self.settings.day_boundary,
+                                DayBoundary::CompletionTime,
+                                "completion",
+                            )
+                            .changed();
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Daily focus goal");
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.settings.daily_goal_minutes)
+                                    .range(5..=720)
+                                    .suffix(" min"),
+                            )
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Daily session goal");
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut self.settings.daily_goal_sessions).range(0..=24))
+                            .on_hover_text("Pomodoros to aim for each day, shown as dots on the work screen (0 = none)")
+                            .changed();
+                        changed |= ui
+                            .add_enabled(
+                                self.settings.daily_goal_sessions > 0,
+                                egui::Checkbox::new(&mut self.settings.goal_notification, "Notify when reached"),
+                            )
+                            .changed();
+                    });
+                    changed |= ui
+                        .checkbox(&mut self.settings.goal_requires_break, "Count toward goal only after the break")
+                        .on_hover_text("A session counts once its break is taken in full; skipping the break forfeits it")
+                        .changed();
+
+                    // Per-weekday goals, defaulting to the daily goal
+                    egui::CollapsingHeader::new("Goals by weekday").show(ui, |ui| {
+                        egui::Grid::new("weekday_goals").num_columns(7).show(ui, |ui| {
+                            for weekday in WEEKDAYS {
+                                ui.label(&weekday.to_string()[..2]);
+                            }
+                            ui.end_row();
+                            for weekday in WEEKDAYS {
+                                let mut minutes = self.settings.goal_minutes_for(weekday);
+                                if ui.add(egui::DragValue::new(&mut minutes).range(0..=720)).changed() {
+                                    self.settings.set_goal_minutes_for(weekday, minutes);
+                                    changed = true;
+                                }
+                            }
+                            ui.end_row();
+                        });
+                        if !self.settings.weekday_goals.is_empty() && ui.small_button("Use daily goal for all").clicked() {
+                            self.settings.weekday_goals.clear();
+                            changed = true;
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        changed |= ui
+                            .checkbox(&mut self.settings.welcome_back_enabled, "Welcome back after")
+                            .on_hover_text("Shown at launch when no session was completed for this many days")
+                            .changed();
+                        changed |= ui
+                            .add_enabled(
+                                self.settings.welcome_back_enabled,
+                                egui::DragValue::new(&mut self.settings.welcome_back_days)
+                                    .range(1..=60)
+                                    .suffix(" days away"),
+                            )
+                            .changed();
+                    });
+                    if self.settings.welcome_back_enabled {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.settings.welcome_back_message)
+                                .hint_text("Welcome back message"),
+                        );
+                        changed |= response.lost_focus();
+                    }
+
+                    ui.horizontal(|ui| {
+                        changed |= ui
+                            .checkbox(&mut self.settings.focus_contract_enabled, "Daily commitment")
+                            .on_hover_text("Commit to a number of sessions each day and get a recap in the evening")
+                            .changed();
+                        changed |= ui
+                            .add_enabled(
+                                self.settings.focus_contract_enabled,
+                                egui::DragValue::new(&mut self.settings.contract_recap_hour)
+                                    .range(0..=23)
+                                    .prefix("recap at ")
+                                    .suffix(":00"),
+                            )
+                            .changed();
+                    });
+
+                    changed |= ui
+                        .checkbox(&mut self.settings.notifications_enabled, "Notify when a period ends")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        changed |= ui
+                            .checkbox(&mut self.settings.wind_down_enabled, "Wind-down warning")
+                            .changed();
+                        changed |= ui
+                            .add_enabled(
+                                self.settings.wind_down_enabled,
+                                egui::DragValue::new(&mut self.settings.wind_down_lead_secs)
+                                    .range(10..=600)
+                                    .suffix(" s before"),
+                            )
+                            .changed();
+                    });
+
+                    ui.separator();
+
+                    // Completion chime
+                    changed |= ui.checkbox(&mut self.settings.sound_enabled, "Chime when a period ends").changed();
+                    ui.add_enabled_ui(self.settings.sound_enabled, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Volume");
+                            changed |= ui
+                                .add(egui::Slider::new(&mut self.settings.sound_volume, 0.0..=1.0).show_value(false))
+                                .changed();
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Sound");
+                            let name = self
+                                .settings
+                                .custom_sound_path()
+                                .and_then(|path| path.file_name().map(|n| n.to_string_lossy().into_owned()))
+                                .unwrap_or_else(|| "Built-in chime".to_string());
+                            ui.label(name);
+                            if ui.small_button("Choose…").clicked() {
+                                pick_sound = true;
+                            }
+                            if self.settings.custom_sound_path().is_some() && ui.small_button("Reset").clicked() {
+                                self.settings.sound_file.clear();
+                                self.sound_warning = None;
+                                changed = true;
+                            }
+                        });
+                        if let Some(warning) = &self.sound_warning {
+                            ui.label(egui::RichText::new(warning).small().color(ui.visuals().warn_fg_color));
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label("Fade in");
+                            changed |= ui
+                                .add(
+                                    egui::DragValue::new(&mut self.settings.sound_fade_in_ms)
+                                        .range(0..=2000)
+                                        .suffix(" ms"),
+                                )
+                                .changed();
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Repeat");
+                            changed |= ui
+                                .add(
+                                    egui::DragValue::new(&mut self.settings.chime_repeats)
+                                        .range(0..=10)
+                                        .suffix(" times"),
+                                )
+                                .on_hover_text("Until you click or press a key in the app")
+                                .changed();
+                            ui.label("every");
+                            changed |= ui
+                                .add_enabled(
+                                    self.settings.chime_repeats > 0,
+                                    egui::DragValue::new(&mut self.settings.chime_repeat_secs)
+                                        .range(3..=120)
+                                        .suffix(" s"),
+                                )
+                                .changed();
+                        });
+                    });
+
+                    // Ambient loop during work
+                    ui.horizontal(|ui| {
+                        ui.label("Background sound");
+                        egui::ComboBox::from_id_salt("ambient_sound")
+                            .selected_text(self.settings.ambient_sound.label())
+                            .show_ui(ui, |ui| {
+                                for sound in AmbientSound::ALL {
+                                    changed |= ui
+                                        .selectable_value(&mut self.settings.ambient_sound, sound, sound.label())
+                                        .changed();
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Loops while a work session is running");
+                    if self.settings.ambient_sound == AmbientSound::Custom {
+                        ui.horizontal(|ui| {
+                            let name = std::path::Path::new(self.settings.ambient_file.trim())
+                                .file_name()
+                                .map_or_else(|| "No file".to_string(), |n| n.to_string_lossy().into_owned());
+                            ui.label(name);
+                            if ui.small_button("Choose…").clicked() {
+                                pick_ambient = true;
+                            }
+                        });
+                    }
+                    ui.add_enabled_ui(self.settings.ambient_sound != AmbientSound::Off, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Background volume");
+                            changed |= ui
+                                .add(egui::Slider::new(&mut self.settings.ambient_volume, 0.0..=1.0).show_value(false))
+                                .changed();
+                        });
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Displays change during a break");
+                        egui::ComboBox::from_id_salt("display_change_action")
+                            .selected_text(self.settings.display_change_action.label())
+                            .show_ui(ui, |ui| {
+                                for action in DisplayChangeAction::ALL {
+                                    changed |= ui
+                                        .selectable_value(&mut self.settings.display_change_action, action, action.label())
+                                        .changed();
+                                }
+                            });
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("When a break ends");
+                        egui::ComboBox::from_id_salt("break_end_action")
+                            .selected_text(self.settings.break_end_action.label())
+                            .show_ui(ui, |ui| {
+                                for action in BreakEndAction::ALL {
+                                    changed |= ui
+                                        .selectable_value(&mut self.settings.break_end_action, action, action.label())
+                                        .changed();
+                                }
+                            });
+                    });
+
+                    ui.separator();
+
+                    // Locked during a break so it can't be used to escape one
+                    let in_break = self.mode.is_break();
+                    changed |= ui
+                        .add_enabled(
+                            !in_break,
+                            egui::Checkbox::new(&mut self.settings.strict_mode, "Strict mode"),
+                        )
+                        .on_hover_text("Breaks can't be skipped")
+                        .on_disabled_hover_text("Can't be changed during a break")
+                        .changed();
+                    changed |= ui
+                        .add_enabled(
+                            !self.settings.strict_mode,
+                            egui::Checkbox::new(&mut self.settings.ask_skip_reason, "Ask why when skipping a break"),
+                        )
+                        .on_hover_text("One click to pick a reason; reasons are saved with the skip")
+                        .changed();
+                    changed |= ui
+                        .checkbox(
+                            &mut self.settings.on_demand_long_break_resets_cycle,
+                            "Early long break restarts the cycle",
+                        )
+                        .on_hover_text("Otherwise a long break taken with ☕ keeps the sessions done so far")
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut self.settings.gentle_first_break, "Windowed first break")
+                        .on_hover_text("The first break of each cycle opens in a window; later ones go fullscreen")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label("Grace before fullscreen");
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.settings.break_grace_secs)
+                                    .range(0..=60)
+                                    .suffix(" s"),
+                            )
+                            .changed();
+                    })
+                    .response
+                    .on_hover_text("Count down in the window first, with time to delay or skip the break");
+                    changed |= ui
+                        .checkbox(&mut self.settings.hide_during_work, "Hide window during work")
+                        .on_hover_text("Run from the menu bar only; the window returns for breaks or via Show Window")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.add_enabled_ui(idle::idle_detection_supported(), |ui| {
+                            ui.label("Pause when idle for");
+                            changed |= ui
+                                .add(
+                                    egui::DragValue::new(&mut self.settings.idle_threshold_secs)
+                                        .range(0..=3600)
+                                        .suffix(" s"),
+                                )
+                                .changed();
+                        });
+                    })
+                    .response
+                    .on_hover_text(if idle::idle_detection_supported() {
+                        "No keyboard or mouse input for this long pauses work, minus the idle time (0 = never)"
+                    } else {
+                        "Idle detection isn't available on this platform"
+                    });
+                    changed |= ui
+                        .add_enabled(
+                            !self.settings.continuous_mode,
+                            egui::Checkbox::new(&mut self.settings.auto_break, "Start breaks automatically"),
+                        )
+                        .on_hover_text("Otherwise the timer stops when work ends until you press Take a Break")
+                        .changed();
+                    changed |= ui
+                        .add_enabled(
+                            !self.settings.continuous_mode,
+                            egui::Checkbox::new(&mut self.settings.auto_start_work, "Start work automatically"),
+                        )
+                        .on_hover_text("Begin the next work session as soon as a break ends")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label("Save sessions ended early after");
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.settings.min_session_seconds)
+                                    .range(0..=1800)
+                                    .suffix(" s"),
+                            )
+                            .changed();
+                    })
+                    .response
+                    .on_hover_text("Take Break during work saves the session only if it ran this long; it then counts toward today like a full one");
+                    changed |= ui
+                        .checkbox(&mut self.settings.continuous_mode, "Continuous focus")
+                        .on_hover_text("Start the next work session as soon as one ends, with no breaks")
+                        .changed();
+
+                    ui.separator();
+
+                    changed |= self.profiles_section(ui);
+
+                    ui.separator();
+
+                    // Key bindings
+                    ui.horizontal(|ui| {
+                        ui.label("Escape during work");
+                        egui::ComboBox::from_id_salt("escape_action")
+                            .selected_text(self.settings.escape_action.label())
+                            .show_ui(ui, |ui| {
+                                for action in EscapeAction::ALL {
+                                    changed |= ui
+                                        .selectable_value(&mut self.settings.escape_action, action, action.label())
+                                        .changed();
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Escape closes an open window first");
+                    ui.label(
+                        egui::RichText::new("During work: Space start/pause · R restart · I interrupt")
+                            .small()
+                            .color(ui.visuals().weak_text_color()),
+                    );
+                    changed |= self.hotkeys_section(ui);
+
+                    ui.separator();
+
+                    // Integrations
+                    ui.horizontal(|ui| {
+                        ui.label("Webhook URL");
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.settings.webhook_url)
+                                .hint_text("https://…"),
+                        );
+                        // Saved once editing finishes rather than on every keystroke
+                        changed |= response.lost_focus();
+                    })
+                    .response
+                    .on_hover_text("Completed work sessions are posted here as JSON");
+                    #[cfg(unix)]
+                    {
+                        socket_changed = ui
+                            .checkbox(&mut self.settings.state_socket_enabled, "Local state socket")
+                            .on_hover_text(format!(
+                                "Timer changes are streamed as JSON lines to {}",
+                                StateSocket::path(self.db.data_dir()).display()
+                            ))
+                            .changed();
+                    }
+                    #[cfg(not(unix))]
+                    {
+                        socket_changed = ui
+                            .add_enabled(false, egui::Checkbox::new(&mut self.settings.state_socket_enabled, "Local state socket"))
+                            .on_disabled_hover_text("The state socket needs Unix domain sockets, which this platform doesn't have")
+                            .changed();
+                    }
+
+                    ui.separator();
+
+                    // Data import
+                    ui.horizontal(|ui| {
+                        let selected = self.import_format.map_or("Auto-detect", |f| f.label());
+                        egui::ComboBox::from_id_salt("import_format")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.import_format, None, "Auto-detect");
+                                for format in ImportFormat::ALL {
+                                    ui.selectable_value(&mut self.import_format, Some(format), format.label());
+                                }
+                            });
+                        import_clicked = ui.button("Import CSV…").clicked();
+                        export_clicked = ui.button("Export CSV…").clicked();
+                    });
+                    if let Some(status) = &self.data_status {
+                        ui.label(status);
+                    }
+                    if ui.button("Open data folder").on_hover_text("Where sessions.db and settings are stored").clicked() {
+                        open_data_folder(self.db.data_dir());
+                    }
+
+                    ui.separator();
+
+                    changed |= self.retention_section(ui);
+                    self.maintenance_section(ui);
+                    self.reset_section(ui);
+                });
+            });
+
+        if import_clicked {
+            self.import_sessions();
+        }
+        if export_clicked {
+            self.export_sessions();
+        }
+        if pick_sound {
+            self.pick_sound_file();
+        }
+        if pick_ambient {
+            self.pick_ambient_file();
+        }
+
+        self.settings_open = open;
+        #[cfg(unix)]
+        if socket_changed {
+            self.apply_state_socket();
+        }
+        if day_rules_changed {
+            self.apply_day_rules();
+        }
+        if durations_changed {
+            self.apply_durations();
+        }
+        if changed || day_rules_changed || socket_changed || durations_changed {
+            self.save_settings();
+        }
+    }
+
+    fn import_sessions(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).pick_file() else {
+            return;
+        };
+
+        self.data_status = Some(match import_csv(&self.db, &path, self.import_format) {
+            Ok(report) => {
+                self.refresh_today_totals();
+                self.refresh_stats();
+                self.load_known_labels();
+                report.summary()
+            }
+            Err(e) => format!("Import failed: {}", e),
+        });
+    }
+
+    fn export_sessions(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name("pocket_flow_sessions.csv")
+            .save_file()
+        else {
+            return;
+        };
+
+        self.data_status = Some(match self.db.export_sessions_csv(&path) {
+            Ok(count) => format!("Exported {} sessions", count),
+            Err(e) => format!("Export failed: {}", e),
+        });
+    }
+
+    fn pick_sound_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Audio", &["wav", "mp3", "ogg", "flac"])
+            .pick_file()
+        else {
+            return;
+        };
+        self.settings.sound_file = path.to_string_lossy().into_owned();
+        self.save_settings();
+        self.check_custom_sound();
+    }
+
+    fn pick_ambient_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Audio", &["wav", "mp3", "ogg", "flac"])
+            .pick_file()
+        else {
+            return;
+        };
+        self.settings.ambient_file = path.to_string_lossy().into_owned();
+        self.save_settings();
+    }
+
+    /// Warn once about a custom sound that won't play; completions fall back to the chime
+    pub(crate) fn check_custom_sound(&mut self) {
+        self.sound_warning = self.settings.custom_sound_path().and_then(|path| {
+            audio::check_sound_file(&path)
+                .err()
+                .map(|e| format!("{}. The built-in chime will play instead.", e))
+        });
+    }
+
+    /// Push the day bucketing settings to the database and recount today
+    fn apply_day_rules(&mut self) {
+        self.db.set_day_boundary(self.settings.day_boundary);
+        self.db.set_day_start_hour(self.settings.day_start_hour);
+        self.refresh_today_totals();
+    }
+}
+
+/// Open the folder holding sessions.db in the system file manager, creating it if needed
+fn open_data_folder(dir: &Path) {
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        eprintln!("Failed to create {}: {}", dir.display(), e);
+        return;
+    }
+    if let Err(e) = opener::open(dir) {
+        eprintln!("Failed to open {}: {}", dir.display(), e);
+    }
+}
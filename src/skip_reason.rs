@@ -0,0 +1,39 @@
+use eframe::egui;
+
+use crate::theme::Theme;
+use crate::PomodoroApp;
+
+/// Preset answers to "Why are you skipping?", stored as-is with the skip
+const SKIP_REASONS: [&str; 5] = ["In the flow", "Deadline", "Meeting", "Not tired", "Other"];
+
+impl PomodoroApp {
+    /// One-click reasons shown in place of the break controls, see Settings::ask_skip_reason
+    pub(crate) fn skip_reason_buttons(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, theme: &Theme) {
+        ui.label(
+            egui::RichText::new("Why are you skipping?")
+                .size(16.0)
+                .color(theme.background)
+        );
+        ui.add_space(8.0);
+
+        let mut chosen = None;
+        let mut keep_break = false;
+        ui.horizontal_wrapped(|ui| {
+            for reason in SKIP_REASONS {
+                if ui.button(egui::RichText::new(reason).size(16.0)).clicked() {
+                    chosen = Some(reason);
+                }
+            }
+        });
+        ui.add_space(8.0);
+        if ui.small_button("Keep my break").clicked() {
+            keep_break = true;
+        }
+
+        if let Some(reason) = chosen {
+            self.confirm_skip(ctx, Some(reason));
+        } else if keep_break {
+            self.skip_reason_prompt = false;
+        }
+    }
+}
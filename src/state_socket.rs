@@ -0,0 +1,123 @@
+use std::io::{ErrorKind, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use chrono::Utc;
+
+const SOCKET_NAME: &str = "pocket_flow.sock";
+const ACCEPT_POLL: Duration = Duration::from_millis(100); // How often new clients are picked up
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1); // A client this slow to read is dropped
+
+/// Streams timer transitions as newline-delimited JSON to any process connected to a
+/// Unix domain socket in the data folder. Clients get the current state on connect, then one
+/// line per event. The socket file is removed when this is dropped.
+pub struct StateSocket {
+    sender: Sender<String>,
+    path: PathBuf,
+}
+
+impl StateSocket {
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(SOCKET_NAME)
+    }
+
+    pub fn start(data_dir: &Path) -> std::io::Result<Self> {
+        let path = Self::path(data_dir);
+        // Left behind by a crash; binding fails while the file exists
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || serve(listener, receiver));
+        Ok(Self { sender, path })
+    }
+
+    /// Queue one event line; `event` is e.g. "start", "pause", "complete" or "mode_change"
+    pub fn send(&self, event: &str, mode: &str, state: &str, remaining_seconds: u64) {
+        let line = format!(
+            r#"{{"event":"{}","mode":"{}","state":"{}","remaining_seconds":{},"at":"{}"}}"#,
+            event,
+            mode,
+            state,
+            remaining_seconds,
+            Utc::now().to_rfc3339(),
+        );
+        // Only fails once the serving thread has stopped, which is already logged
+        let _ = self.sender.send(line);
+    }
+}
+
+impl Drop for StateSocket {
+    fn drop(&mut self) {
+        remove_socket(&self.path);
+    }
+}
+
+fn remove_socket(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != ErrorKind::NotFound {
+            eprintln!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Runs until the StateSocket is dropped, accepting clients and fanning out events
+fn serve(listener: UnixListener, receiver: Receiver<String>) {
+    let mut clients: Vec<UnixStream> = Vec::new();
+    let mut last_line: Option<String> = None;
+
+    loop {
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if let Some(stream) = prepare_client(stream, last_line.as_deref()) {
+                        clients.push(stream);
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    eprintln!("Failed to accept state socket client: {}", e);
+                    break;
+                }
+            }
+        }
+
+        match receiver.recv_timeout(ACCEPT_POLL) {
+            Ok(line) => {
+                // Clients that hung up or stopped reading are dropped
+                clients.retain_mut(|client| write_line(client, &line));
+                last_line = Some(line);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+fn prepare_client(mut stream: UnixStream, current: Option<&str>) -> Option<UnixStream> {
+    // Accepted sockets can inherit non-blocking mode from the listener
+    if let Err(e) = stream
+        .set_nonblocking(false)
+        .and_then(|()| stream.set_write_timeout(Some(WRITE_TIMEOUT)))
+    {
+        eprintln!("Failed to set up state socket client: {}", e);
+        return None;
+    }
+    match current {
+        Some(line) if !write_line(&mut stream, line) => None,
+        _ => Some(stream),
+    }
+}
+
+fn write_line(stream: &mut UnixStream, line: &str) -> bool {
+    stream
+        .write_all(line.as_bytes())
+        .and_then(|()| stream.write_all(b"\n"))
+        .is_ok()
+}
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Weekday};
+use rusqlite::Result;
+
+use crate::db::Database;
+use crate::models::{DailySummary, EndReason, WorkSession};
+
+/// Sessions and focus time for one day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DayTotal {
+    pub day: NaiveDate,
+    pub sessions: usize,
+    pub focus_seconds: i64,
+    pub goal_seconds: i64, // Focus time that counts toward the daily goal
+}
+
+impl From<&DailySummary> for DayTotal {
+    fn from(summary: &DailySummary) -> Self {
+        Self {
+            day: summary.day,
+            sessions: summary.sessions,
+            focus_seconds: summary.focus_seconds,
+            goal_seconds: summary.goal_seconds,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Summary {
+    pub sessions: usize,
+    pub focus_seconds: i64,
+    pub active_days: usize,              // Days with at least one session
+    pub interruptions: usize,            // Recorded during the sessions
+    pub busiest_weekday: Option<Weekday>, // Most focus time; None without sessions
+    pub busiest_hour: Option<u32>,       // Local hour sessions start in with the most focus time
+}
+
+/// Aggregates over the sessions of a range of days. The range is loaded once,
+/// and every breakdown is computed from that snapshot.
+pub struct Stats {
+    first: NaiveDate,
+    last: NaiveDate,
+    sessions: Vec<(NaiveDate, WorkSession)>, // Each session with the day it counts toward
+}
+
+impl Stats {
+    /// Sessions from first to last (inclusive), bucketed by the database's day rules
+    pub fn for_range(db: &Database, first: NaiveDate, last: NaiveDate) -> Result<Self> {
+        let sessions = db
+            .get_sessions_between(first, last)?
+            .into_iter()
+            .map(|session| (db.session_day(&session), session))
+            .collect();
+        Ok(Self::from_sessions(first, last, sessions))
+    }
+
+    /// Build from already-bucketed sessions, without touching the database
+    pub fn from_sessions(
+        first: NaiveDate,
+        last: NaiveDate,
+        sessions: Vec<(NaiveDate, WorkSession)>,
+    ) -> Self {
+        Self { first, last, sessions }
+    }
+
+    /// Keep only sessions that ran their full length, dropping early, interrupted and
+    /// clock-affected ones
+    pub fn completed_only(mut self) -> Self {
+        self.sessions.retain(|(_, session)| session.end_reason == EndReason::Completed);
+        self
+    }
+
+    /// One entry per day in the range, with zeros for days without sessions
+    pub fn daily_counts(&self) -> Vec<DayTotal> {
+        let mut totals: Vec<DayTotal> = self
+            .first
+            .iter_days()
+            .take_while(|day| *day <= self.last)
+            .map(|day| DayTotal {
+                day,
+                sessions: 0,
+                focus_seconds: 0,
+                goal_seconds: 0,
+            })
+            .collect();
+
+        for (day, session) in &self.sessions {
+            let offset = (*day - self.first).num_days();
+            if let Some(total) = usize::try_from(offset).ok().and_then(|i| totals.get_mut(i)) {
+                total.sessions += 1;
+                total.focus_seconds = total.focus_seconds.saturating_add(session.duration_seconds);
+                if session.counts_toward_goal {
+                    total.goal_seconds = total.goal_seconds.saturating_add(session.duration_seconds);
+                }
+            }
+        }
+
+        totals
+    }
+
+    /// Focus seconds per weekday, starting with Monday
+    pub fn by_weekday(&self) -> [i64; 7] {
+        let mut totals = [0i64; 7];
+        for (day, session) in &self.sessions {
+            let total = &mut totals[day.weekday().num_days_from_monday() as usize];
+            *total = total.saturating_add(session.duration_seconds);
+        }
+        totals
+    }
+
+    /// Focus seconds per local hour of day the sessions started in
+    pub fn by_hour(&self) -> [i64; 24] {
+        let mut totals = [0i64; 24];
+        for (_, session) in &self.sessions {
+            let hour = session.started_at.with_timezone(&Local).hour() as usize;
+            totals[hour] = totals[hour].saturating_add(session.duration_seconds);
+        }
+        totals
+    }
+
+    /// Local start time of each day's first session, for days that had one
+    pub fn first_focus(&self) -> Vec<(NaiveDate, DateTime<Local>)> {
+        let mut firsts: Vec<(NaiveDate, DateTime<Local>)> = Vec::new();
+        for (day, session) in &self.sessions {
+            let started = session.started_at.with_timezone(&Local);
+            match firsts.iter_mut().find(|(first_day, _)| first_day == day) {
+                Some((_, first)) if started < *first => *first = started,
+                Some(_) => {}
+                None => firsts.push((*day, started)),
+            }
+        }
+        firsts.sort_by_key(|(day, _)| *day);
+        firsts
+    }
+
+    pub fn summary(&self) -> Summary {
+        let daily = self.daily_counts();
+        Summary {
+            sessions: self.sessions.len(),
+            focus_seconds: daily.iter().fold(0, |sum, total| sum.saturating_add(total.focus_seconds)),
+            active_days: daily.iter().filter(|total| total.sessions > 0).count(),
+            interruptions: self.sessions.iter().map(|(_, session)| session.interruptions as usize).sum(),
+            busiest_weekday: busiest(&self.by_weekday())
+                .map(|i| Weekday::try_from(i as u8).expect("Index of a weekday")),
+            busiest_hour: busiest(&self.by_hour()).map(|i| i as u32),
+        }
+    }
+}
+
+/// Consecutive days with a session, ending today or, while today has none yet, yesterday.
+/// Only counts as far back as `totals` reaches.
+pub fn current_streak(totals: &[DayTotal]) -> usize {
+    let mut days = totals.iter().rev().peekable();
+    if days.peek().is_some_and(|today| today.sessions == 0) {
+        days.next();
+    }
+    days.take_while(|total| total.sessions > 0).count()
+}
+
+/// Like current_streak, over sorted, distinct days that had a session
+pub fn streak_ending(days: &[NaiveDate], today: NaiveDate) -> u32 {
+    let mut expected = match days.last() {
+        Some(last) if *last == today => today,
+        Some(last) if Some(*last) == today.pred_opt() => *last,
+        _ => return 0,
+    };
+    let mut streak = 0;
+    for day in days.iter().rev() {
+        if *day != expected {
+            break;
+        }
+        streak += 1;
+        match expected.pred_opt() {
+            Some(previous) => expected = previous,
+            None => break,
+        }
+    }
+    streak
+}
+
+/// Longest run of consecutive days in sorted, distinct days
+pub fn longest_streak(days: &[NaiveDate]) -> u32 {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days {
+        run = match previous {
+            Some(previous) if previous.succ_opt() == Some(*day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+    longest
+}
+
+/// Index of the largest positive total, preferring the earliest on ties
+fn busiest(totals: &[i64]) -> Option<usize> {
+    totals
+        .iter()
+        .enumerate()
+        .filter(|(_, total)| **total > 0)
+        .fold(None, |best: Option<(usize, i64)>, (i, total)| match best {
+            Some((_, best_total)) if best_total >= *total => best,
+            _ => Some((i, *total)),
+        })
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2024, 3, day, hour, minute, 0).unwrap().with_timezone(&Utc)
+    }
+
+    fn march(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    /// Monday the 4th has two sessions, one not counting toward the goal, Tuesday none and
+    /// Wednesday one long one. Friday's falls outside the range.
+    fn week_db() -> Database {
+        let db = Database::open_in_memory(Arc::new(MockClock::at(local(8, 18, 0)))).unwrap();
+        let mut sessions = vec![
+            WorkSession::new(local(4, 9, 0), local(4, 9, 25)),
+            WorkSession::new(local(4, 14, 0), local(4, 14, 25)),
+            WorkSession::new(local(6, 9, 0), local(6, 10, 0)),
+            WorkSession::new(local(8, 9, 0), local(8, 9, 25)),
+        ];
+        sessions[1].counts_toward_goal = false;
+        sessions[2].interruptions = 2;
+        for session in &sessions {
+            db.insert_work_session(session).unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn daily_counts_cover_every_day_in_range() {
+        let stats = Stats::for_range(&week_db(), march(4), march(6)).unwrap();
+
+        let counts: Vec<_> = stats
+            .daily_counts()
+            .iter()
+            .map(|total| (total.day, total.sessions, total.focus_seconds, total.goal_seconds))
+            .collect();
+        assert_eq!(
+            counts,
+            [(march(4), 2, 3000, 1500), (march(5), 0, 0, 0), (march(6), 1, 3600, 3600)]
+        );
+    }
+
+    #[test]
+    fn summary_adds_up_the_range() {
+        let stats = Stats::for_range(&week_db(), march(4), march(6)).unwrap();
+
+        assert_eq!(
+            stats.summary(),
+            Summary {
+                sessions: 3,
+                focus_seconds: 6600,
+                active_days: 2,
+                interruptions: 2,
+                busiest_weekday: Some(Weekday::Wed),
+                busiest_hour: Some(9),
+            }
+        );
+    }
+
+    #[test]
+    fn empty_range_has_no_busiest_times() {
+        let stats = Stats::for_range(&week_db(), march(5), march(5)).unwrap();
+
+        assert_eq!(stats.summary(), Summary::default());
+        assert_eq!(stats.daily_counts().len(), 1);
+    }
+
+    #[test]
+    fn streak_ending_stops_at_the_first_gap() {
+        let days = [march(1), march(2), march(4), march(5), march(6)];
+
+        assert_eq!(streak_ending(&days, march(6)), 3);
+        // Today has nothing yet, so the run up to yesterday still counts
+        assert_eq!(streak_ending(&days, march(7)), 3);
+        assert_eq!(streak_ending(&days, march(8)), 0);
+        assert_eq!(streak_ending(&[], march(6)), 0);
+    }
+
+    #[test]
+    fn longest_streak_finds_the_longest_run_between_gaps() {
+        let days = [march(1), march(2), march(3), march(5), march(7), march(8)];
+
+        assert_eq!(longest_streak(&days), 3);
+        assert_eq!(longest_streak(&[march(5)]), 1);
+        assert_eq!(longest_streak(&[]), 0);
+    }
+}
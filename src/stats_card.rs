@@ -0,0 +1,174 @@
+use std::path::Path;
+
+use ab_glyph::{point, Font, FontArc, PxScale, ScaleFont};
+use chrono::NaiveDate;
+use eframe::egui::{Color32, FontDefinitions};
+use image::{Rgba, RgbaImage};
+
+use crate::stats::{current_streak, DayTotal};
+use crate::theme::Theme;
+use crate::PomodoroApp;
+
+// Fixed size so every shared card looks the same
+const CARD_WIDTH: u32 = 640;
+const CARD_HEIGHT: u32 = 360;
+const MARGIN: f32 = 32.0;
+const CHART_TOP: f32 = 200.0;
+const CHART_BOTTOM: f32 = 310.0;
+
+/// What goes on the card, taken from the stats window's loaded data
+pub struct StatsCard {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+    pub sessions: usize,
+    pub focus_seconds: i64,
+    pub streak: usize,
+    pub week: Vec<DayTotal>, // Last seven days, oldest first
+}
+
+impl StatsCard {
+    pub fn from_totals(totals: &[DayTotal], sessions: usize, focus_seconds: i64) -> Option<Self> {
+        let (first, last) = (totals.first()?.day, totals.last()?.day);
+        Some(Self {
+            first,
+            last,
+            sessions,
+            focus_seconds,
+            streak: current_streak(totals),
+            week: totals[totals.len().saturating_sub(7)..].to_vec(),
+        })
+    }
+
+    pub fn render(&self, theme: &Theme) -> RgbaImage {
+        let mut card = Canvas::new(theme.main);
+        let text = theme.background;
+
+        card.text("Pocket Flow", MARGIN, 56.0, 30.0, theme.accent);
+        let range = format!("{} – {}", self.first.format("%b %d"), self.last.format("%b %d, %Y"));
+        card.text_right(&range, CARD_WIDTH as f32 - MARGIN, 52.0, 16.0, text);
+
+        let figures = [
+            (self.sessions.to_string(), "sessions"),
+            (format!("{:.1} h", self.focus_seconds as f64 / 3600.0), "focused"),
+            (self.streak.to_string(), "day streak"),
+        ];
+        let column = (CARD_WIDTH as f32 - 2.0 * MARGIN) / figures.len() as f32;
+        for (i, (value, caption)) in figures.iter().enumerate() {
+            let x = MARGIN + column * i as f32;
+            card.text(value, x, 128.0, 40.0, text);
+            card.text(caption, x, 154.0, 15.0, text);
+        }
+
+        // Focus per day over the last week
+        let max = self.week.iter().map(|total| total.focus_seconds).max().unwrap_or(0).max(1);
+        let slot = (CARD_WIDTH as f32 - 2.0 * MARGIN) / self.week.len().max(1) as f32;
+        for (i, total) in self.week.iter().enumerate() {
+            let x = MARGIN + slot * i as f32;
+            let height = (CHART_BOTTOM - CHART_TOP) * total.focus_seconds as f32 / max as f32;
+            card.rect(x + slot * 0.2, CHART_BOTTOM - height.max(2.0), slot * 0.6, height.max(2.0), theme.accent);
+            let day = total.day.format("%a").to_string();
+            card.text_centered(&day, x + slot / 2.0, CHART_BOTTOM + 22.0, 14.0, text);
+        }
+
+        card.image
+    }
+}
+
+impl PomodoroApp {
+    /// Render the current stats to a PNG chosen with a save dialog
+    pub(crate) fn save_stats_image(&self) {
+        let Some(card) = StatsCard::from_totals(
+            &self.daily_totals,
+            self.stats_summary.sessions,
+            self.stats_summary.focus_seconds,
+        ) else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PNG image", &["png"])
+            .set_file_name(format!("pocket_flow_{}.png", card.last.format("%Y-%m-%d")))
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(e) = save_png(&card.render(self.theme()), &path) {
+            eprintln!("Failed to save stats image: {}", e);
+        }
+    }
+}
+
+fn save_png(image: &RgbaImage, path: &Path) -> image::ImageResult<()> {
+    image.save_with_format(path, image::ImageFormat::Png)
+}
+
+/// Software painter for the card: solid rectangles and antialiased text in egui's own font
+struct Canvas {
+    image: RgbaImage,
+    font: FontArc,
+}
+
+impl Canvas {
+    fn new(background: Color32) -> Self {
+        let fonts = FontDefinitions::default();
+        let data = fonts.font_data.get("Ubuntu-Light").expect("egui ships Ubuntu-Light");
+        Self {
+            image: RgbaImage::from_pixel(CARD_WIDTH, CARD_HEIGHT, rgba(background)),
+            font: FontArc::try_from_vec(data.font.to_vec()).expect("Built-in font parses"),
+        }
+    }
+
+    fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color32) {
+        let clamp_x = |v: f32| (v.max(0.0) as u32).min(CARD_WIDTH);
+        let clamp_y = |v: f32| (v.max(0.0) as u32).min(CARD_HEIGHT);
+        for py in clamp_y(y)..clamp_y(y + height) {
+            for px in clamp_x(x)..clamp_x(x + width) {
+                self.image.put_pixel(px, py, rgba(color));
+            }
+        }
+    }
+
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        let font = self.font.as_scaled(PxScale::from(size));
+        text.chars().map(|c| font.h_advance(font.glyph_id(c))).sum()
+    }
+
+    fn text_right(&mut self, text: &str, right: f32, baseline: f32, size: f32, color: Color32) {
+        let x = right - self.text_width(text, size);
+        self.text(text, x, baseline, size, color);
+    }
+
+    fn text_centered(&mut self, text: &str, center: f32, baseline: f32, size: f32, color: Color32) {
+        let x = center - self.text_width(text, size) / 2.0;
+        self.text(text, x, baseline, size, color);
+    }
+
+    fn text(&mut self, text: &str, x: f32, baseline: f32, size: f32, color: Color32) {
+        let font = self.font.as_scaled(PxScale::from(size));
+        let mut caret = x;
+        for c in text.chars() {
+            let glyph = font.glyph_id(c).with_scale_and_position(size, point(caret, baseline));
+            caret += font.h_advance(glyph.id);
+            let Some(outline) = self.font.outline_glyph(glyph) else { continue };
+            let bounds = outline.px_bounds();
+            let image = &mut self.image;
+            outline.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i32 + gx as i32;
+                let py = bounds.min.y as i32 + gy as i32;
+                if px < 0 || py < 0 || px >= CARD_WIDTH as i32 || py >= CARD_HEIGHT as i32 {
+                    return;
+                }
+                let pixel = image.get_pixel_mut(px as u32, py as u32);
+                *pixel = blend(*pixel, color, coverage);
+            });
+        }
+    }
+}
+
+fn rgba(color: Color32) -> Rgba<u8> {
+    Rgba([color.r(), color.g(), color.b(), 255])
+}
+
+fn blend(under: Rgba<u8>, over: Color32, coverage: f32) -> Rgba<u8> {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * coverage.clamp(0.0, 1.0)).round() as u8;
+    Rgba([mix(under[0], over.r()), mix(under[1], over.g()), mix(under[2], over.b()), 255])
+}
@@ -0,0 +1,663 @@
+use eframe::egui;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike, Weekday};
+
+use crate::models::EndReason;
+use crate::stats::{DayTotal, Stats};
+use crate::PomodoroApp;
+
+const HISTORY_LIMIT: usize = 50;
+const HISTORY_LOAD_MARGIN: f32 = 24.0; // The next page loads once the list is scrolled this close to its end
+const HEATMAP_WEEKS: i64 = 5;
+const TIMELINE_HEIGHT: f32 = 16.0;
+const FIRST_FOCUS_CHART_HEIGHT: f32 = 40.0;
+const WEEK_BAR_WIDTH: f32 = 140.0; // The week's busiest day
+const HOUR_CELL_MAX: f32 = 14.0; // Hour cells shrink below this to fit the window
+
+// Diverging scale for focus minutes relative to the daily goal
+const COLOR_BELOW_GOAL: egui::Color32 = egui::Color32::from_rgb(0xD9, 0x53, 0x4F); // #D9534F
+const COLOR_AT_GOAL: egui::Color32 = egui::Color32::from_rgb(0xE8, 0xE0, 0xC8); // #E8E0C8
+const COLOR_ABOVE_GOAL: egui::Color32 = egui::Color32::from_rgb(0x2E, 0x9E, 0x5B); // #2E9E5B
+
+impl PomodoroApp {
+    pub(crate) fn refresh_stats(&mut self) {
+        // Keeps however many pages were already loaded
+        let limit = self.history.len().max(HISTORY_LIMIT);
+        match self.db.get_recent_sessions(limit, 0) {
+            Ok(sessions) => {
+                self.history_complete = sessions.len() < limit;
+                self.history = sessions;
+            }
+            Err(e) => eprintln!("Failed to load session history: {}", e),
+        }
+
+        match self.db.get_recent_break_sessions(HISTORY_LIMIT) {
+            Ok(breaks) => self.break_history = breaks,
+            Err(e) => eprintln!("Failed to load break history: {}", e),
+        }
+
+        match self.db.get_sessions_for_day(self.db.current_day()) {
+            Ok(sessions) => self.today_sessions = sessions,
+            Err(e) => eprintln!("Failed to load today's sessions: {}", e),
+        }
+
+        // Whole weeks starting on Monday, ending with the current one
+        let today = self.db.current_day();
+        let first = today
+            - Duration::days(today.weekday().num_days_from_monday() as i64 + (HEATMAP_WEEKS - 1) * 7);
+        match Stats::for_range(&self.db, first, today) {
+            Ok(stats) => {
+                let stats = if self.settings.stats_completed_only { stats.completed_only() } else { stats };
+                // The stored rollups count every session, so they only stand in when unfiltered
+                self.daily_totals = match self.db.get_daily_summaries(first, today) {
+                    Ok(summaries) if !self.settings.stats_completed_only => {
+                        summaries.iter().map(DayTotal::from).collect()
+                    }
+                    Ok(_) => stats.daily_counts(),
+                    Err(e) => {
+                        eprintln!("Failed to load daily summaries: {}", e);
+                        stats.daily_counts()
+                    }
+                };
+                self.stats_summary = stats.summary();
+                self.first_focus = stats.first_focus();
+            }
+            Err(e) => eprintln!("Failed to load daily totals: {}", e),
+        }
+        // The filtered week comes from the same filtered sessions as the heatmap
+        if self.settings.stats_completed_only {
+            let week = &self.daily_totals[self.daily_totals.len().saturating_sub(7)..];
+            self.week_totals = week.iter().map(|total| (total.day, total.sessions, total.focus_seconds)).collect();
+        } else {
+            match self.db.get_sessions_per_day(7) {
+                Ok(week) => self.week_totals = week,
+                Err(e) => eprintln!("Failed to load the last 7 days: {}", e),
+            }
+        }
+        match self.db.get_break_counts(first, today) {
+            Ok(counts) => self.break_counts = counts,
+            Err(e) => eprintln!("Failed to load break counts: {}", e),
+        }
+        match (self.db.get_total_session_count(), self.db.get_total_focus_seconds()) {
+            (Ok(sessions), Ok(seconds)) => self.all_time_totals = (sessions, seconds),
+            (Err(e), _) | (_, Err(e)) => eprintln!("Failed to load all-time totals: {}", e),
+        }
+        self.refresh_streaks();
+        match self.db.get_focus_seconds_by_label() {
+            Ok(totals) => self.label_totals = totals,
+            Err(e) => eprintln!("Failed to load focus by label: {}", e),
+        }
+        match self.db.get_focus_seconds_by_tag() {
+            Ok(totals) => self.tag_totals = totals,
+            Err(e) => eprintln!("Failed to load focus by tag: {}", e),
+        }
+        match self.db.get_focus_by_hour() {
+            Ok(hours) => self.hourly_focus = hours,
+            Err(e) => eprintln!("Failed to load focus by hour: {}", e),
+        }
+    }
+
+    /// Also kept current outside the stats window, for the goal screen
+    pub(crate) fn refresh_streaks(&mut self) {
+        match (self.db.get_current_streak(), self.db.get_longest_streak()) {
+            (Ok(current), Ok(longest)) => self.streaks = (current, longest),
+            (Err(e), _) | (_, Err(e)) => eprintln!("Failed to load streaks: {}", e),
+        }
+    }
+
+    /// Asks before deleting the session picked in the history list, since it can't be undone
+    fn confirm_delete_prompt(&mut self, ctx: &egui::Context) {
+        let Some(session) = &self.delete_pending else { return };
+        let description = format!(
+            "{} · {}",
+            session.started_at.with_timezone(&Local).format("%b %d  %H:%M"),
+            self.settings.number_locale.duration(session.duration_seconds)
+        );
+
+        let mut decided = None;
+        egui::Window::new("Delete session?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(description);
+                ui.label("It will no longer count toward today or your stats.");
+                ui.horizontal(|ui| {
+                    if ui.button("Delete").clicked() {
+                        decided = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        decided = Some(false);
+                    }
+                });
+            });
+
+        match decided {
+            Some(true) => {
+                if let Some(id) = self.delete_pending.take().and_then(|session| session.id) {
+                    self.delete_session(id);
+                }
+            }
+            Some(false) => self.delete_pending = None,
+            None => {}
+        }
+    }
+
+    fn delete_session(&mut self, id: i64) {
+        if let Err(e) = self.db.delete_session(id) {
+            eprintln!("Failed to delete session {}: {}", id, e);
+            return;
+        }
+        if self.goal_pending_session == Some(id) {
+            self.goal_pending_session = None;
+        }
+        self.refresh_today_totals();
+        self.load_known_labels();
+        self.refresh_stats();
+    }
+
+    /// Next page of older sessions for the history list
+    fn load_more_history(&mut self) {
+        match self.db.get_recent_sessions(HISTORY_LIMIT, self.history.len()) {
+            Ok(sessions) => {
+                self.history_complete = sessions.len() < HISTORY_LIMIT;
+                self.history.extend(sessions);
+            }
+            Err(e) => eprintln!("Failed to load session history: {}", e),
+        }
+    }
+
+    /// Headline number: everything focused since the first session
+    fn all_time_summary(&self, ui: &mut egui::Ui) {
+        let (sessions, seconds) = self.all_time_totals;
+        let locale = self.settings.number_locale;
+        ui.label(
+            egui::RichText::new(format!(
+                "All time: {} focused over {} sessions",
+                locale.hours_minutes(seconds),
+                locale.integer(sessions as i64)
+            ))
+            .strong(),
+        );
+        let (current, longest) = self.streaks;
+        if longest > 0 {
+            let days = |n: u32| format!("{} day{}", n, if n == 1 { "" } else { "s" });
+            ui.label(format!("Streak: {} · longest {}", days(current), days(longest)));
+        }
+    }
+
+    /// Calendar grid of recent days, colored by focus minutes against each day's goal
+    fn focus_heatmap(&self, ui: &mut egui::Ui) {
+        let cell = 18.0;
+        let today = self.db.current_day();
+        let goal_for = |day: &NaiveDate| self.settings.goal_minutes_for(day.weekday()).max(1) as f32;
+
+        // Read every frame so the goal follows the day across midnight
+        let today_minutes = self
+            .daily_totals
+            .iter()
+            .find(|total| total.day == today)
+            .map_or(0.0, |total| total.goal_seconds as f32 / 60.0);
+        ui.label(
+            egui::RichText::new(format!("Today: {:.0} / {:.0} min goal", today_minutes, goal_for(&today)))
+                .strong(),
+        );
+        ui.add_space(4.0);
+
+        for week in self.daily_totals.chunks(7) {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 3.0;
+                for total in week {
+                    let (rect, response) =
+                        ui.allocate_exact_size(egui::vec2(cell, cell), egui::Sense::hover());
+                    let minutes = total.goal_seconds as f32 / 60.0;
+                    let goal_minutes = goal_for(&total.day);
+                    ui.painter().rect_filled(rect, 3.0, goal_color(minutes / goal_minutes));
+                    let mut tooltip = format!(
+                        "{}: {:.0} / {:.0} min ({} sessions)",
+                        total.day.format("%a %b %d"),
+                        minutes,
+                        goal_minutes,
+                        total.sessions
+                    );
+                    let pending_minutes = (total.focus_seconds - total.goal_seconds) / 60;
+                    if pending_minutes > 0 {
+                        tooltip.push_str(&format!("\n{} min not counted: break not taken", pending_minutes));
+                    }
+                    response.on_hover_text(tooltip);
+                }
+            });
+        }
+    }
+
+    /// Sessions and focus time for each of the last seven days, today last
+    fn week_bars(&self, ui: &mut egui::Ui) {
+        let max = self.week_totals.iter().map(|(_, _, seconds)| *seconds).max().unwrap_or(0).max(1);
+        let locale = self.settings.number_locale;
+
+        egui::CollapsingHeader::new("Last 7 days").default_open(true).show(ui, |ui| {
+            for &(day, sessions, focus_seconds) in &self.week_totals {
+                ui.horizontal(|ui| {
+                    ui.add_sized([72.0, 14.0], egui::Label::new(day.format("%a %b %d").to_string()));
+                    let width = WEEK_BAR_WIDTH * focus_seconds as f32 / max as f32;
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(WEEK_BAR_WIDTH, 10.0), egui::Sense::hover());
+                    let bar = egui::Rect::from_min_size(rect.min, egui::vec2(width.max(1.0), rect.height()));
+                    ui.painter().rect_filled(bar, 2.0, self.theme().accent);
+                    ui.label(format!(
+                        "{} · {}",
+                        locale.integer(sessions as i64),
+                        locale.duration(focus_seconds)
+                    ));
+                });
+            }
+        });
+    }
+
+    /// Totals over the heatmap's weeks, with when focus usually happens
+    fn period_summary(&self, ui: &mut egui::Ui) {
+        let summary = &self.stats_summary;
+        let locale = self.settings.number_locale;
+        ui.label(format!(
+            "Last {} weeks: {} sessions, {} h over {} days",
+            HEATMAP_WEEKS,
+            locale.integer(summary.sessions as i64),
+            locale.decimal(summary.focus_seconds as f64 / 3600.0),
+            summary.active_days
+        ));
+        if let (Some(weekday), Some(hour)) = (summary.busiest_weekday, summary.busiest_hour) {
+            ui.label(format!("Most focused on {}s, around {:02}:00", weekday_name(weekday), hour));
+        }
+        if summary.interruptions > 0 {
+            ui.label(format!(
+                "Interruptions: {} ({} per session)",
+                locale.integer(summary.interruptions as i64),
+                locale.decimal(summary.interruptions as f64 / summary.sessions.max(1) as f64)
+            ));
+        }
+
+        let (taken, skipped) = self.break_counts;
+        if let Some(percent_taken) = (taken * 100).checked_div(taken + skipped) {
+            ui.label(format!(
+                "Breaks taken: {} · skipped: {} ({}% taken)",
+                locale.integer(taken as i64),
+                locale.integer(skipped as i64),
+                percent_taken
+            ));
+        }
+    }
+
+    /// All-time focus per local hour of day as a strip of 24 cells, darker the more focus
+    /// started in that hour
+    fn hour_heatmap(&self, ui: &mut egui::Ui) {
+        let max = self.hourly_focus.iter().copied().max().unwrap_or(0);
+        if max == 0 {
+            return;
+        }
+        let theme = self.theme();
+        let locale = self.settings.number_locale;
+        egui::CollapsingHeader::new("By hour of day").default_open(true).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                let cell = ((ui.available_width() - 23.0 * 2.0) / 24.0).clamp(4.0, HOUR_CELL_MAX);
+                for (hour, seconds) in self.hourly_focus.iter().enumerate() {
+                    let (rect, response) = ui.allocate_exact_size(egui::vec2(cell, cell), egui::Sense::hover());
+                    let color = if *seconds > 0 {
+                        theme.accent.gamma_multiply(0.15 + 0.85 * *seconds as f32 / max as f32)
+                    } else {
+                        theme.secondary_dark
+                    };
+                    ui.painter().rect_filled(rect, 2.0, color);
+                    response.on_hover_text(format!(
+                        "{:02}:00–{:02}:00: {}",
+                        hour,
+                        (hour + 1) % 24,
+                        locale.hours_minutes(*seconds)
+                    ));
+                }
+            });
+            ui.label(
+                egui::RichText::new("Sessions count toward the hour they started in")
+                    .small()
+                    .color(ui.visuals().weak_text_color()),
+            );
+        });
+    }
+
+    /// All-time focus per task label, shown once anything has been labeled
+    fn label_breakdown(&self, ui: &mut egui::Ui) {
+        self.focus_breakdown(ui, "By label", &self.label_totals, "Unlabeled");
+    }
+
+    /// All-time focus per project tag, shown once anything has been tagged
+    fn tag_breakdown(&self, ui: &mut egui::Ui) {
+        self.focus_breakdown(ui, "By tag", &self.tag_totals, "Untagged");
+    }
+
+    fn focus_breakdown(&self, ui: &mut egui::Ui, title: &str, totals: &[(Option<String>, i64)], none: &str) {
+        if totals.iter().all(|(name, _)| name.is_none()) {
+            return;
+        }
+        let locale = self.settings.number_locale;
+        egui::CollapsingHeader::new(title).show(ui, |ui| {
+            for (name, seconds) in totals {
+                ui.horizontal(|ui| {
+                    match name {
+                        Some(name) => ui.label(name),
+                        None => ui.label(egui::RichText::new(none).color(ui.visuals().weak_text_color())),
+                    };
+                    ui.label(locale.hours_minutes(*seconds));
+                });
+            }
+        });
+    }
+
+    /// Recent breaks, with which were skipped and why
+    fn break_history_list(&self, ui: &mut egui::Ui) {
+        if self.break_history.is_empty() {
+            return;
+        }
+        egui::CollapsingHeader::new("Recent breaks").show(ui, |ui| {
+            egui::ScrollArea::vertical().id_salt("break_history").max_height(120.0).show(ui, |ui| {
+                for session in &self.break_history {
+                    ui.horizontal(|ui| {
+                        ui.label(session.started_at.with_timezone(&Local).format("%b %d  %H:%M").to_string());
+                        ui.label(self.settings.number_locale.duration(session.duration_seconds));
+                        if session.long_break {
+                            ui.label(egui::RichText::new("Long").color(ui.visuals().weak_text_color()));
+                        }
+                        if session.skipped {
+                            let skipped = ui.label(
+                                egui::RichText::new("Skipped").small().color(ui.visuals().warn_fg_color),
+                            );
+                            if let Some(reason) = &session.skip_reason {
+                                skipped.on_hover_text(reason);
+                            }
+                        }
+                    });
+                }
+            });
+        });
+    }
+
+    /// Today's first session start, and how first starts have moved over the heatmap's weeks
+    fn first_focus_chart(&self, ui: &mut egui::Ui) {
+        let today = self.db.current_day();
+        let text = match self.first_focus.iter().find(|(day, _)| *day == today) {
+            Some((_, started)) => format!("First focus today: {}", started.format("%H:%M")),
+            None => "No focus yet today".to_string(),
+        };
+        ui.label(text);
+
+        if self.first_focus.len() < 2 {
+            return;
+        }
+
+        // Minutes after the day starts, so night owls past midnight plot after the evening
+        let day_start_minutes = self.settings.day_start_hour as i64 * 60;
+        let minutes_into_day = |started: &DateTime<Local>| {
+            (started.hour() as i64 * 60 + started.minute() as i64 - day_start_minutes).rem_euclid(24 * 60)
+        };
+        let earliest = self.first_focus.iter().map(|(_, t)| minutes_into_day(t)).min().unwrap_or(0);
+        let latest = self.first_focus.iter().map(|(_, t)| minutes_into_day(t)).max().unwrap_or(0);
+        let span = (latest - earliest).max(60) as f32;
+
+        let first_day = match self.daily_totals.first() {
+            Some(total) => total.day,
+            None => return,
+        };
+        let days = self.daily_totals.len().max(2) as f32 - 1.0;
+
+        let (rect, _) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), FIRST_FOCUS_CHART_HEIGHT),
+            egui::Sense::hover(),
+        );
+        let theme = self.theme();
+        ui.painter().rect_filled(rect, 3.0, theme.secondary_dark);
+
+        let points: Vec<(egui::Pos2, String)> = self
+            .first_focus
+            .iter()
+            .map(|(day, started)| {
+                let x = rect.left() + 4.0 + (rect.width() - 8.0) * (*day - first_day).num_days() as f32 / days;
+                // Earlier starts sit higher
+                let y = rect.top() + 4.0
+                    + (rect.height() - 8.0) * (minutes_into_day(started) - earliest) as f32 / span;
+                let tooltip = format!("{}: {}", day.format("%a %b %d"), started.format("%H:%M"));
+                (egui::pos2(x, y), tooltip)
+            })
+            .collect();
+
+        ui.painter().add(egui::Shape::line(
+            points.iter().map(|(point, _)| *point).collect(),
+            egui::Stroke::new(1.0, theme.accent.gamma_multiply(0.5)),
+        ));
+        for (point, tooltip) in points {
+            ui.painter().circle_filled(point, 2.5, theme.accent);
+            let hit = egui::Rect::from_center_size(point, egui::vec2(8.0, 8.0));
+            ui.interact(hit, ui.id().with(("first_focus", tooltip.as_str())), egui::Sense::hover())
+                .on_hover_text(tooltip);
+        }
+    }
+
+    /// Today's sessions as segments along the day, with gaps left empty
+    fn today_timeline(&self, ui: &mut egui::Ui) {
+        let (day_start, day_end) = self.db.get_day_bounds(self.db.current_day());
+        let day_seconds = (day_end - day_start).num_seconds().max(1) as f32;
+        let theme = self.theme();
+
+        ui.label(egui::RichText::new("Today").strong());
+        ui.add_space(4.0);
+
+        let (rect, _) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), TIMELINE_HEIGHT),
+            egui::Sense::hover(),
+        );
+        ui.painter().rect_filled(rect, 3.0, theme.secondary_dark);
+
+        // Fraction of the day elapsed at a timestamp, clamped to the strip
+        let x_at = |time: chrono::DateTime<chrono::Utc>| {
+            let fraction = ((time - day_start).num_seconds() as f32 / day_seconds).clamp(0.0, 1.0);
+            rect.left() + rect.width() * fraction
+        };
+
+        for session in &self.today_sessions {
+            let left = x_at(session.started_at);
+            // Keep very short sessions visible
+            let right = x_at(session.completed_at).max(left + 2.0);
+            let segment = egui::Rect::from_x_y_ranges(left..=right, rect.y_range());
+            ui.painter().rect_filled(segment, 2.0, theme.accent);
+
+            let started = session.started_at.with_timezone(&Local);
+            let completed = session.completed_at.with_timezone(&Local);
+            let mut tooltip = format!("{} – {}", started.format("%H:%M"), completed.format("%H:%M"));
+            if let Some(label) = &session.label {
+                tooltip.push_str(&format!("\n{}", label));
+            }
+            if let Some(note) = &session.note {
+                tooltip.push_str(&format!("\n{}", note));
+            }
+            ui.interact(segment, ui.id().with(("timeline", session.id)), egui::Sense::hover())
+                .on_hover_text(tooltip);
+        }
+
+        // Hour labels at the start, middle and end of the day
+        let start_label = day_start.with_timezone(&Local).format("%H:%M").to_string();
+        let middle = day_start + (day_end - day_start) / 2;
+        let middle_label = middle.with_timezone(&Local).format("%H:%M").to_string();
+        ui.columns(3, |columns| {
+            columns[0].small(start_label.clone());
+            columns[1].vertical_centered(|ui| ui.small(middle_label));
+            columns[2].with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                ui.small(start_label)
+            });
+        });
+    }
+
+    pub(crate) fn show_stats_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.stats_open;
+        let mut saved_note = None;
+        let mut load_more = false;
+        let mut delete_requested = None;
+
+        egui::Window::new("Stats")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().id_salt("stats").show(ui, |ui| {
+                    self.all_time_summary(ui);
+                    ui.add_space(4.0);
+                    self.today_timeline(ui);
+                    ui.separator();
+                    self.focus_heatmap(ui);
+                    ui.add_space(4.0);
+                    self.week_bars(ui);
+                    ui.add_space(4.0);
+                    self.period_summary(ui);
+                    ui.add_space(4.0);
+                    self.first_focus_chart(ui);
+                    if ui.small_button("Save image…").on_hover_text("Save this summary as a PNG to share").clicked() {
+                        self.save_stats_image();
+                    }
+                    if ui
+                        .checkbox(&mut self.settings.stats_completed_only, "Naturally completed sessions only")
+                        .on_hover_text("Leave out interrupted sessions and ones affected by clock changes")
+                        .changed()
+                    {
+                        self.save_settings();
+                        self.refresh_stats();
+                    }
+                    self.hour_heatmap(ui);
+                    self.label_breakdown(ui);
+                    self.tag_breakdown(ui);
+                    self.break_history_list(ui);
+                    ui.separator();
+
+                    ui.label(egui::RichText::new("History").strong());
+                    ui.add_space(4.0);
+
+                    if self.history.is_empty() {
+                        ui.label("No completed sessions yet");
+                        return;
+                    }
+
+                    let list = egui::ScrollArea::vertical().id_salt("history").max_height(240.0).show(ui, |ui| {
+                        for session in &self.history {
+                            let Some(id) = session.id else { continue };
+                            let started = session.started_at.with_timezone(&Local);
+
+                            ui.horizontal(|ui| {
+                                ui.label(started.format("%b %d  %H:%M").to_string());
+                                ui.label(self.settings.number_locale.duration(session.duration_seconds));
+                                if let Some(label) = &session.label {
+                                    ui.label(egui::RichText::new(label).color(ui.visuals().weak_text_color()));
+                                }
+                                if session.pause_count > 0 {
+                                    ui.label(
+                                        egui::RichText::new(format!("⏸{}", session.pause_count))
+                                            .small()
+                                            .color(ui.visuals().weak_text_color()),
+                                    )
+                                    .on_hover_text(format!(
+                                        "Paused {} time{}, {} in all",
+                                        session.pause_count,
+                                        if session.pause_count == 1 { "" } else { "s" },
+                                        self.settings.number_locale.duration(session.paused_seconds)
+                                    ));
+                                }
+                                if session.end_reason != EndReason::Completed {
+                                    ui.label(
+                                        egui::RichText::new(session.end_reason.label())
+                                            .small()
+                                            .color(ui.visuals().warn_fg_color),
+                                    );
+                                }
+
+                                // Note indicator, showing the note on hover
+                                if let Some(note) = &session.note {
+                                    ui.label("📝").on_hover_text(note);
+                                }
+
+                                let editing = matches!(&self.editing_note, Some((edit_id, _)) if *edit_id == id);
+                                if !editing
+                                    && ui.small_button("✏").on_hover_text("Edit note").clicked()
+                                {
+                                    self.editing_note = Some((id, session.note.clone().unwrap_or_default()));
+                                }
+                                if ui.small_button("🗑").on_hover_text("Delete session").clicked() {
+                                    delete_requested = Some(session.clone());
+                                }
+                            });
+
+                            // Inline note editor for the selected session
+                            if let Some((edit_id, text)) = &mut self.editing_note {
+                                if *edit_id == id {
+                                    ui.horizontal(|ui| {
+                                        let response = ui.text_edit_singleline(text);
+                                        let submitted = response.lost_focus()
+                                            && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                        if ui.small_button("Save").clicked() || submitted {
+                                            saved_note = Some((id, text.clone()));
+                                        }
+                                    });
+                                }
+                            }
+                        }
+                    });
+                    let scrolled_to = list.state.offset.y + list.inner_rect.height();
+                    load_more = !self.history_complete && scrolled_to >= list.content_size.y - HISTORY_LOAD_MARGIN;
+                });
+            });
+
+        if load_more {
+            self.load_more_history();
+        }
+        if delete_requested.is_some() {
+            self.delete_pending = delete_requested;
+        }
+        if open {
+            self.confirm_delete_prompt(ctx);
+        } else {
+            self.delete_pending = None;
+        }
+        if let Some((id, note)) = saved_note {
+            if let Err(e) = self.db.update_session_note(id, Some(&note)) {
+                eprintln!("Failed to save session note: {}", e);
+            }
+            self.editing_note = None;
+            self.refresh_stats();
+        }
+
+        self.stats_open = open;
+    }
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// Below the goal fades from red toward neutral, above it deepens toward green
+fn goal_color(ratio: f32) -> egui::Color32 {
+    if ratio < 1.0 {
+        lerp_color(COLOR_BELOW_GOAL, COLOR_AT_GOAL, ratio.max(0.0))
+    } else {
+        // Twice the goal or more gets the strongest green
+        lerp_color(COLOR_AT_GOAL, COLOR_ABOVE_GOAL, (ratio - 1.0).min(1.0))
+    }
+}
+
+fn lerp_color(from: egui::Color32, to: egui::Color32, t: f32) -> egui::Color32 {
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    egui::Color32::from_rgb(
+        channel(from.r(), to.r()),
+        channel(from.g(), to.g()),
+        channel(from.b(), to.b()),
+    )
+}
@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const BUILT_IN_SUGGESTIONS: [&str; 8] = [
+    "Stand up and stretch your back",
+    "Look at something 20 feet away for 20 seconds",
+    "Refill your water glass",
+    "Take a few slow, deep breaths",
+    "Roll your shoulders and neck",
+    "Walk around the room",
+    "Rest your eyes with them closed",
+    "Open a window and get some fresh air",
+];
+
+/// Break suggestions read from break_suggestions.txt (one per line) in the data directory,
+/// falling back to the built-in list when the file is missing or empty
+pub struct BreakSuggestions {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    user_suggestions: Vec<String>,
+    last_index: Option<usize>,
+}
+
+impl BreakSuggestions {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join("break_suggestions.txt"),
+            modified: None,
+            user_suggestions: Vec::new(),
+            last_index: None,
+        }
+    }
+
+    /// Pick a random suggestion, never the same one twice in a row.
+    /// Re-reads the file first if it changed since the last pick.
+    pub fn next(&mut self) -> String {
+        self.reload_if_changed();
+
+        let count = if self.user_suggestions.is_empty() {
+            BUILT_IN_SUGGESTIONS.len()
+        } else {
+            self.user_suggestions.len()
+        };
+
+        // Clock nanos are random enough for picking a suggestion
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as usize)
+            .unwrap_or(0);
+        let mut index = seed % count;
+        if count > 1 && self.last_index == Some(index) {
+            index = (index + 1) % count;
+        }
+        self.last_index = Some(index);
+
+        if self.user_suggestions.is_empty() {
+            BUILT_IN_SUGGESTIONS[index].to_string()
+        } else {
+            self.user_suggestions[index].clone()
+        }
+    }
+
+    fn reload_if_changed(&mut self) {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+
+        self.user_suggestions = std::fs::read_to_string(&self.path)
+            .map(|contents| {
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.last_index = None;
+    }
+}
@@ -0,0 +1,159 @@
+use eframe::egui;
+use egui::Color32;
+
+use crate::config::ThemeMode;
+
+/// A named color palette applied to the whole app
+pub struct Theme {
+    pub name: &'static str,
+    pub main: Color32,           // Panel background
+    pub background: Color32,     // Text on the panel (light, despite the name)
+    pub accent: Color32,         // Session dots and highlights
+    pub secondary: Color32,      // Hovered/active buttons
+    pub secondary_dark: Color32, // Idle buttons
+    pub dark: bool,              // Built on egui's dark visuals rather than the light ones
+}
+
+// Color Palette (the original dark blue look)
+pub const MIDNIGHT: Theme = Theme {
+    name: "Midnight",
+    main: Color32::from_rgb(0x00, 0x12, 0x40),           // #001240
+    background: Color32::from_rgb(0xFA, 0xFA, 0xFA),     // #FAFAFA
+    accent: Color32::from_rgb(0xFF, 0x73, 0x1C),         // #FF731C
+    secondary: Color32::from_rgb(0x60, 0x9E, 0xF6),      // #609EF6
+    secondary_dark: Color32::from_rgb(0x16, 0x46, 0xA1), // #1646A1
+    dark: true,
+};
+
+pub const OCEAN: Theme = Theme {
+    name: "Ocean",
+    main: Color32::from_rgb(0x0B, 0x2A, 0x3C),           // #0B2A3C
+    background: Color32::from_rgb(0xE8, 0xF6, 0xFA),     // #E8F6FA
+    accent: Color32::from_rgb(0xFF, 0xB3, 0x47),         // #FFB347
+    secondary: Color32::from_rgb(0x4F, 0xC3, 0xD9),      // #4FC3D9
+    secondary_dark: Color32::from_rgb(0x14, 0x6C, 0x82), // #146C82
+    dark: true,
+};
+
+pub const FOREST: Theme = Theme {
+    name: "Forest",
+    main: Color32::from_rgb(0x14, 0x2A, 0x1E),           // #142A1E
+    background: Color32::from_rgb(0xF1, 0xF5, 0xEC),     // #F1F5EC
+    accent: Color32::from_rgb(0xE8, 0xB0, 0x4B),         // #E8B04B
+    secondary: Color32::from_rgb(0x7B, 0xC4, 0x7F),      // #7BC47F
+    secondary_dark: Color32::from_rgb(0x2E, 0x6B, 0x3F), // #2E6B3F
+    dark: true,
+};
+
+pub const SOLARIZED: Theme = Theme {
+    name: "Solarized",
+    main: Color32::from_rgb(0x00, 0x2B, 0x36),           // #002B36 base03
+    background: Color32::from_rgb(0xFD, 0xF6, 0xE3),     // #FDF6E3 base3
+    accent: Color32::from_rgb(0xCB, 0x4B, 0x16),         // #CB4B16 orange
+    secondary: Color32::from_rgb(0x26, 0x8B, 0xD2),      // #268BD2 blue
+    secondary_dark: Color32::from_rgb(0x07, 0x36, 0x42), // #073642 base02
+    dark: true,
+};
+
+pub const PRESETS: [&Theme; 4] = [&MIDNIGHT, &OCEAN, &FOREST, &SOLARIZED];
+
+// Light versions: panel and text colors swap, accents are darkened to stay readable on the
+// light panel, and idle buttons become a pale tint so their dark text shows
+const MIDNIGHT_LIGHT: Theme = Theme {
+    name: "Midnight",
+    main: Color32::from_rgb(0xF4, 0xF6, 0xFB),           // #F4F6FB
+    background: Color32::from_rgb(0x00, 0x12, 0x40),     // #001240
+    accent: Color32::from_rgb(0xC2, 0x54, 0x0A),         // #C2540A
+    secondary: Color32::from_rgb(0x2F, 0x6F, 0xD0),      // #2F6FD0
+    secondary_dark: Color32::from_rgb(0xD3, 0xE2, 0xFB), // #D3E2FB
+    dark: false,
+};
+
+const OCEAN_LIGHT: Theme = Theme {
+    name: "Ocean",
+    main: Color32::from_rgb(0xEE, 0xF8, 0xFB),           // #EEF8FB
+    background: Color32::from_rgb(0x0B, 0x2A, 0x3C),     // #0B2A3C
+    accent: Color32::from_rgb(0xB0, 0x62, 0x00),         // #B06200
+    secondary: Color32::from_rgb(0x1E, 0x86, 0x9E),      // #1E869E
+    secondary_dark: Color32::from_rgb(0xCD, 0xEB, 0xF2), // #CDEBF2
+    dark: false,
+};
+
+const FOREST_LIGHT: Theme = Theme {
+    name: "Forest",
+    main: Color32::from_rgb(0xF3, 0xF6, 0xEF),           // #F3F6EF
+    background: Color32::from_rgb(0x14, 0x2A, 0x1E),     // #142A1E
+    accent: Color32::from_rgb(0x93, 0x62, 0x0C),         // #93620C
+    secondary: Color32::from_rgb(0x3E, 0x8A, 0x47),      // #3E8A47
+    secondary_dark: Color32::from_rgb(0xD4, 0xE9, 0xD3), // #D4E9D3
+    dark: false,
+};
+
+const SOLARIZED_LIGHT: Theme = Theme {
+    name: "Solarized",
+    main: Color32::from_rgb(0xFD, 0xF6, 0xE3),           // #FDF6E3 base3
+    background: Color32::from_rgb(0x07, 0x36, 0x42),     // #073642 base02
+    accent: Color32::from_rgb(0xCB, 0x4B, 0x16),         // #CB4B16 orange
+    secondary: Color32::from_rgb(0x26, 0x8B, 0xD2),      // #268BD2 blue
+    secondary_dark: Color32::from_rgb(0xEE, 0xE8, 0xD5), // #EEE8D5 base2
+    dark: false,
+};
+
+const LIGHT_PRESETS: [&Theme; 4] = [&MIDNIGHT_LIGHT, &OCEAN_LIGHT, &FOREST_LIGHT, &SOLARIZED_LIGHT];
+
+impl Theme {
+    /// Look up a preset by name, falling back to Midnight for unknown names
+    pub fn by_name(name: &str) -> &'static Theme {
+        PRESETS
+            .into_iter()
+            .find(|theme| theme.name == name)
+            .unwrap_or(&MIDNIGHT)
+    }
+
+    /// The preset with this name in the given mode
+    pub fn by_name_for(name: &str, mode: ThemeMode) -> &'static Theme {
+        let dark = Self::by_name(name);
+        match mode {
+            ThemeMode::Dark => dark,
+            ThemeMode::Light => LIGHT_PRESETS
+                .into_iter()
+                .find(|theme| theme.name == dark.name)
+                .unwrap_or(&MIDNIGHT_LIGHT),
+        }
+    }
+}
+
+pub fn apply_theme(ctx: &egui::Context, theme: &Theme) {
+    ctx.style_mut(|style| {
+        // Start from egui's matching base so unthemed parts (text fields, scrollbars) fit in
+        style.visuals = if theme.dark { egui::Visuals::dark() } else { egui::Visuals::light() };
+
+        // Set overall background color
+        style.visuals.panel_fill = theme.main;
+        style.visuals.window_fill = theme.main;
+
+        // Text in the theme's text color
+        style.visuals.override_text_color = Some(theme.background);
+
+        // Button styling - inverted (dark inactive, light hover)
+        style.visuals.widgets.inactive.weak_bg_fill = theme.secondary_dark;
+        style.visuals.widgets.inactive.bg_fill = theme.secondary_dark;
+        style.visuals.widgets.inactive.fg_stroke.color = theme.background;
+
+        style.visuals.widgets.hovered.weak_bg_fill = theme.secondary;
+        style.visuals.widgets.hovered.bg_fill = theme.secondary;
+        style.visuals.widgets.hovered.fg_stroke.color = theme.main;
+
+        style.visuals.widgets.active.weak_bg_fill = theme.secondary;
+        style.visuals.widgets.active.bg_fill = theme.secondary;
+        style.visuals.widgets.active.fg_stroke.color = theme.main;
+
+        // Rounding for buttons
+        style.visuals.widgets.inactive.rounding = egui::Rounding::same(8.0);
+        style.visuals.widgets.hovered.rounding = egui::Rounding::same(8.0);
+        style.visuals.widgets.active.rounding = egui::Rounding::same(8.0);
+
+        // Button padding
+        style.spacing.button_padding = egui::vec2(16.0, 8.0);
+    });
+}
@@ -0,0 +1,94 @@
+use eframe::egui;
+use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tray_icon::{TrayIcon, TrayIconBuilder};
+
+use crate::{PomodoroApp, PomodoroMode, TimerState};
+
+const START_PAUSE_ID: &str = "start_pause";
+const RESET_ID: &str = "reset";
+const SHOW_WINDOW_ID: &str = "show_window";
+const LONG_BREAK_ID: &str = "long_break";
+const QUIT_ID: &str = "quit";
+
+/// Menu items whose label or enabled state follows the timer
+pub struct TrayMenu {
+    menu: Menu,
+    start_pause: MenuItem,
+    reset: MenuItem,
+}
+
+impl TrayMenu {
+    pub fn new() -> Self {
+        let menu = Menu::new();
+        let start_pause = MenuItem::with_id(START_PAUSE_ID, "Start", true, None);
+        let reset = MenuItem::with_id(RESET_ID, "Reset", false, None);
+        let show_window = MenuItem::with_id(SHOW_WINDOW_ID, "Show Window", true, None);
+        let long_break = MenuItem::with_id(LONG_BREAK_ID, "Take a Long Break", true, None);
+        let quit = MenuItem::with_id(QUIT_ID, "Quit", true, None);
+        if let Err(e) = menu.append_items(&[
+            &start_pause,
+            &reset,
+            &PredefinedMenuItem::separator(),
+            &show_window,
+            &long_break,
+            &PredefinedMenuItem::separator(),
+            &quit,
+        ]) {
+            eprintln!("Failed to build tray menu: {}", e);
+        }
+        Self { menu, start_pause, reset }
+    }
+
+    pub fn sync(&self, mode: PomodoroMode, state: TimerState, break_pending: bool) {
+        self.start_pause.set_text(if state.is_running() { "Pause" } else { "Start" });
+        self.start_pause.set_enabled(!break_pending);
+        self.reset.set_enabled(mode == PomodoroMode::Work && state != TimerState::Stopped);
+    }
+}
+
+/// Menu bar item showing the countdown, with a menu to control the timer, bring the window
+/// back when it's hidden during work, or start a long break
+pub fn build_tray_icon(tray_menu: &TrayMenu) -> Option<TrayIcon> {
+    TrayIconBuilder::new()
+        .with_title("25:00")
+        .with_tooltip("Pocket Flow - Pomodoro Timer")
+        .with_menu(Box::new(tray_menu.menu.clone()))
+        .build()
+        .map_err(|e| eprintln!("Failed to create tray icon: {}", e))
+        .ok()
+}
+
+impl PomodoroApp {
+    pub(crate) fn handle_tray_events(&mut self, ctx: &egui::Context) {
+        while let Ok(event) = MenuEvent::receiver().try_recv() {
+            if event.id() == START_PAUSE_ID {
+                self.toggle_start_pause(ctx);
+            } else if event.id() == RESET_ID {
+                if self.mode == PomodoroMode::Work {
+                    self.stop();
+                }
+            } else if event.id() == SHOW_WINDOW_ID {
+                self.show_window(ctx);
+            } else if event.id() == LONG_BREAK_ID {
+                self.take_long_break(ctx);
+            } else if event.id() == QUIT_ID {
+                // Goes through on_exit, which saves the timer state and flushes webhooks
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+
+    /// Same as the Start/Pause button on whichever screen is showing
+    pub(crate) fn toggle_start_pause(&mut self, ctx: &egui::Context) {
+        if self.pending_break.is_some() {
+            return;
+        }
+        if self.state.is_running() {
+            self.pause();
+        } else if self.mode.is_break() && self.remaining_seconds == 0 {
+            self.start_work(ctx);
+        } else {
+            self.start(ctx);
+        }
+    }
+}
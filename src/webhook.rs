@@ -0,0 +1,100 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::models::WorkSession;
+
+const MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2); // Doubles after every failed attempt
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Delivery {
+    url: String,
+    payload: String,
+}
+
+/// Posts completion events to a user-configured URL from a background thread.
+/// Events queue up in order and each is retried with exponential backoff before being dropped.
+pub struct Webhook {
+    sender: Sender<Delivery>,
+    queue: Arc<QueueState>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    pending: AtomicUsize,      // Queued or in-flight deliveries
+    shutting_down: AtomicBool, // Skip backoff so the queue drains quickly on exit
+}
+
+impl Webhook {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let queue = Arc::new(QueueState::default());
+        let worker_queue = Arc::clone(&queue);
+        std::thread::spawn(move || deliver_all(receiver, &worker_queue));
+        Self { sender, queue }
+    }
+
+    /// Give queued deliveries until the timeout to go out, one attempt each.
+    /// Returns how many were still pending.
+    pub fn flush(&self, timeout: Duration) -> usize {
+        self.queue.shutting_down.store(true, Ordering::SeqCst);
+        let deadline = Instant::now() + timeout;
+        loop {
+            let pending = self.queue.pending.load(Ordering::SeqCst);
+            if pending == 0 || Instant::now() >= deadline {
+                return pending;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    pub fn send_session_completed(&self, url: &str, session: &WorkSession) {
+        let payload = format!(
+            r#"{{"event":"work_session_completed","started_at":"{}","completed_at":"{}","duration_seconds":{},"end_reason":"{}"}}"#,
+            session.started_at.to_rfc3339(),
+            session.completed_at.to_rfc3339(),
+            session.duration_seconds,
+            session.end_reason.as_str(),
+        );
+        let delivery = Delivery {
+            url: url.to_string(),
+            payload,
+        };
+        self.queue.pending.fetch_add(1, Ordering::SeqCst);
+        if self.sender.send(delivery).is_err() {
+            self.queue.pending.fetch_sub(1, Ordering::SeqCst);
+            eprintln!("Failed to queue webhook: delivery thread has stopped");
+        }
+    }
+}
+
+fn deliver_all(receiver: Receiver<Delivery>, queue: &QueueState) {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+
+    for delivery in receiver {
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let result = agent
+                .post(&delivery.url)
+                .set("Content-Type", "application/json")
+                .send_string(&delivery.payload);
+
+            let last_attempt = attempt == MAX_ATTEMPTS || queue.shutting_down.load(Ordering::SeqCst);
+            match result {
+                Ok(_) => break,
+                Err(e) if last_attempt => {
+                    eprintln!("Dropping webhook to {} after {} attempts: {}", delivery.url, attempt, e);
+                    break;
+                }
+                Err(e) => {
+                    eprintln!("Webhook attempt {} failed, retrying in {:?}: {}", attempt, backoff, e);
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                }
+            }
+        }
+        queue.pending.fetch_sub(1, Ordering::SeqCst);
+    }
+}
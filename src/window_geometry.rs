@@ -0,0 +1,98 @@
+use eframe::egui;
+
+use crate::PomodoroApp;
+
+const FALLBACK_POSITION: egui::Pos2 = egui::pos2(40.0, 40.0); // Used when the saved spot may be off-screen
+
+/// Where the main window was on screen, saved at quit and restored at the next launch.
+/// All values are in points, as egui reports them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    pub position: egui::Pos2,             // Outer top-left, including decorations
+    pub size: egui::Vec2,                 // Inner size, as passed to with_inner_size
+    pub monitor_size: Option<egui::Vec2>, // Monitor the window was on, to notice a changed setup
+}
+
+impl WindowGeometry {
+    /// Stored as "x,y,width,height[,monitor width,monitor height]"
+    pub fn to_meta(self) -> String {
+        let mut value = format!("{},{},{},{}", self.position.x, self.position.y, self.size.x, self.size.y);
+        if let Some(monitor) = self.monitor_size {
+            value.push_str(&format!(",{},{}", monitor.x, monitor.y));
+        }
+        value
+    }
+
+    pub fn from_meta(value: &str) -> Option<Self> {
+        let parts: Vec<f32> = value.split(',').map(|p| p.trim().parse().ok()).collect::<Option<_>>()?;
+        if parts.iter().any(|v| !v.is_finite()) {
+            return None;
+        }
+        let (position, size, monitor_size) = match parts[..] {
+            [x, y, w, h] => (egui::pos2(x, y), egui::vec2(w, h), None),
+            [x, y, w, h, mw, mh] => (egui::pos2(x, y), egui::vec2(w, h), Some(egui::vec2(mw, mh))),
+            _ => return None,
+        };
+        (size.x > 0.0 && size.y > 0.0).then_some(Self { position, size, monitor_size })
+    }
+
+    /// Apply to the builder for the main viewport at launch
+    pub fn apply(&self, builder: egui::ViewportBuilder, min_size: [f32; 2]) -> egui::ViewportBuilder {
+        builder
+            .with_position(self.position)
+            .with_inner_size(self.size.max(egui::Vec2::from(min_size)))
+    }
+}
+
+impl PomodoroApp {
+    /// Remember where the window sits while it's a normal window. Fullscreen breaks, minimized
+    /// and hidden windows are skipped so the next launch opens where the user last put it.
+    pub(crate) fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (outer, inner, fullscreen, minimized, monitor_size) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.outer_rect, viewport.inner_rect, viewport.fullscreen, viewport.minimized, viewport.monitor_size)
+        });
+
+        if self.restore_check_pending {
+            self.check_restored_geometry(ctx, monitor_size);
+            return;
+        }
+
+        let fullscreen_break = self.mode.is_break() && !self.break_window_minimized;
+        if fullscreen == Some(true) || minimized == Some(true) || fullscreen_break || self.window_hidden {
+            return;
+        }
+        if let (Some(outer), Some(inner)) = (outer, inner) {
+            self.window_geometry = Some(WindowGeometry {
+                position: outer.min,
+                size: inner.size(),
+                monitor_size,
+            });
+        }
+    }
+
+    /// Runs once the window knows its monitor. If the monitor setup changed since the position
+    /// was saved (undocked, display unplugged), the old spot may be off-screen, so the window is
+    /// moved back to a known-visible corner and shrunk to fit.
+    fn check_restored_geometry(&mut self, ctx: &egui::Context, monitor_size: Option<egui::Vec2>) {
+        let Some(monitor) = monitor_size else { return };
+        self.restore_check_pending = false;
+        let Some(saved) = self.window_geometry else { return };
+
+        if saved.monitor_size.is_some_and(|m| m == monitor) {
+            return;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(FALLBACK_POSITION));
+        let fitted = saved.size.min(monitor - FALLBACK_POSITION.to_vec2());
+        if fitted != saved.size {
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(fitted));
+        }
+    }
+
+    pub(crate) fn save_window_geometry(&self) {
+        let Some(geometry) = self.window_geometry else { return };
+        if let Err(e) = self.db.set_window_geometry(&geometry) {
+            eprintln!("Failed to save window position: {}", e);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::models::WorkSession;
+
+/// Bookkeeping for one work period: when it started by the wall clock and how long it has
+/// spent paused. Pauses are timed on the monotonic clock, with the instant passed in by the
+/// caller, so any sequence of pauses and resumes can be replayed deterministically.
+#[derive(Debug, Clone)]
+pub struct WorkPeriod {
+    started_at: DateTime<Utc>,
+    planned_seconds: u64,          // Timer length when the period started
+    paused_since: Option<Instant>, // Set while paused
+    paused: Duration,              // Finished pauses
+    pause_count: u32,              // Pauses started, not counting the one a restored period begins in
+    resumed: bool,                 // Restored after the app quit mid-session
+    interruptions: u32,
+    interruption_notes: Vec<String>,
+}
+
+impl WorkPeriod {
+    pub fn start(started_at: DateTime<Utc>, planned_seconds: u64) -> Self {
+        Self {
+            started_at,
+            planned_seconds,
+            paused_since: None,
+            paused: Duration::ZERO,
+            pause_count: 0,
+            resumed: false,
+            interruptions: 0,
+            interruption_notes: Vec::new(),
+        }
+    }
+
+    /// A period carried over from the last run, paused as of `at`
+    pub fn restored(started_at: DateTime<Utc>, planned_seconds: u64, at: Instant) -> Self {
+        Self {
+            paused_since: Some(at),
+            resumed: true,
+            ..Self::start(started_at, planned_seconds)
+        }
+    }
+
+    /// Pausing an already paused period keeps the original pause start and isn't counted again
+    pub fn pause(&mut self, at: Instant) {
+        if self.paused_since.is_none() {
+            self.paused_since = Some(at);
+            self.pause_count += 1;
+        }
+    }
+
+    /// Resuming a period that isn't paused does nothing
+    pub fn resume(&mut self, at: Instant) {
+        if let Some(since) = self.paused_since.take() {
+            self.paused += at.saturating_duration_since(since);
+        }
+    }
+
+    /// Total pause time, including a pause still in progress at `at`
+    pub fn paused_seconds(&self, at: Instant) -> i64 {
+        let ongoing = self.paused_since.map_or(Duration::ZERO, |since| at.saturating_duration_since(since));
+        (self.paused + ongoing).as_secs() as i64
+    }
+
+    pub fn record_interruption(&mut self) {
+        self.interruptions += 1;
+    }
+
+    /// What caused an interruption, kept with the session's note
+    pub fn note_interruption(&mut self, note: &str) {
+        self.interruption_notes.push(note.to_string());
+    }
+
+    pub fn interruptions(&self) -> u32 {
+        self.interruptions
+    }
+
+    pub fn planned_seconds(&self) -> u64 {
+        self.planned_seconds
+    }
+
+    pub fn was_resumed(&self) -> bool {
+        self.resumed
+    }
+
+    /// The session to save for this period; its duration leaves out the time spent paused
+    pub fn finish(&self, completed_at: DateTime<Utc>, at: Instant) -> WorkSession {
+        let mut session = WorkSession::new(self.started_at, completed_at);
+        session.paused_seconds = self.paused_seconds(at);
+        session.duration_seconds -= session.paused_seconds;
+        session.interruptions = self.interruptions;
+        session.pause_count = self.pause_count;
+        if !self.interruption_notes.is_empty() {
+            session.note = Some(format!("Interruptions: {}", self.interruption_notes.join("; ")));
+        }
+        session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn focus_time_leaves_out_every_pause() {
+        let started_at = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let t0 = Instant::now();
+        let at = |seconds| t0 + Duration::from_secs(seconds);
+        let mut period = WorkPeriod::start(started_at, 25 * 60);
+
+        // Work 0-300, paused 300-420, work 420-900, paused 900-1500, work 1500-1800
+        period.pause(at(300));
+        period.pause(at(360)); // Already paused, so ignored
+        period.resume(at(420));
+        period.resume(at(480)); // Not paused, so ignored
+        period.pause(at(900));
+        assert_eq!(period.paused_seconds(at(1000)), 220);
+        period.resume(at(1500));
+
+        let session = period.finish(started_at + chrono::Duration::seconds(1800), at(1800));
+        assert_eq!(session.paused_seconds, 720);
+        assert_eq!(session.duration_seconds, 1080);
+        assert_eq!(session.pause_count, 2);
+    }
+}